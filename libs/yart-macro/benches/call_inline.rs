@@ -0,0 +1,51 @@
+//! Compares awaiting a trivial tool body inline with spawning it on its own task.
+//!
+//! The output type doesn't implement `JsonSchema`, so neither path pays for the
+//! `validate-output` check, which would otherwise dwarf the difference.
+//!
+//! Run with `cargo bench -p yart-macro --bench call_inline`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct BenchArgs {
+    query: String,
+}
+
+#[derive(Serialize)]
+pub struct BenchOutput {
+    query: String,
+}
+
+#[yart::rig_tool(description = "A trivial tool awaited inline", inline = true)]
+async fn inline_tool(args: BenchArgs) -> anyhow::Result<BenchOutput, yart::ToolError> {
+    Ok(BenchOutput { query: args.query })
+}
+
+#[yart::rig_tool(description = "A trivial tool spawned on its own task")]
+async fn spawned_tool(args: BenchArgs) -> anyhow::Result<BenchOutput, yart::ToolError> {
+    Ok(BenchOutput { query: args.query })
+}
+
+async fn call<T: Tool<Args = BenchArgs>>(tool: &T) {
+    let args = BenchArgs {
+        query: "bench".to_string(),
+    };
+    black_box(tool.call(args).await.ok());
+}
+
+fn bench_call(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (spawned, inline) = (SpawnedTool::new(), InlineTool::new());
+    let mut group = c.benchmark_group("call");
+    group.bench_function("spawned", |b| b.to_async(&runtime).iter(|| call(&spawned)));
+    group.bench_function("inline", |b| b.to_async(&runtime).iter(|| call(&inline)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_call);
+criterion_main!(benches);
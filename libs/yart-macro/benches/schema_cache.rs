@@ -0,0 +1,40 @@
+//! Compares the cached `parameters_schema()` with deriving the schema on every call.
+//!
+//! Run with `cargo bench -p yart-macro --bench schema_cache`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct BenchArgs {
+    query: String,
+    limit: u32,
+    filters: Vec<String>,
+}
+
+#[yart::rig_tool(description = "A tool used to benchmark schema generation")]
+async fn bench_tool(args: BenchArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(args.query)
+}
+
+fn time(iterations: u32, f: impl Fn() -> serde_json::Value) -> Duration {
+    let started = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    started.elapsed()
+}
+
+fn main() {
+    let iterations = 10_000;
+    let uncached = time(iterations, yart::derive_parameters::<BenchArgs>);
+    let cached = time(iterations, BenchTool::parameters_schema);
+    println!(
+        "derive_parameters: {:?}/call, cached parameters_schema: {:?}/call ({:.1}x faster)",
+        uncached / iterations,
+        cached / iterations,
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -1,13 +1,17 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::str::FromStr;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::{
-    parse_macro_input, parse_quote, Expr, ExprLit, FnArg, ItemFn, Lit, Meta, ReturnType, Token,
-    Type,
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, ExprLit, FnArg, Generics,
+    Ident, ImplItem, ItemFn, ItemImpl, Lit, Meta, MetaNameValue, Pat, ReturnType, Token, Type,
+    Visibility,
 };
+use yart_shared::RenameRule;
 
 // Convert snake_case to UpperCamelCase (e.g., find_token_metadata -> FindTokenMetadata)
 fn to_upper_camel_case(s: &str) -> String {
@@ -22,73 +26,469 @@ fn to_upper_camel_case(s: &str) -> String {
         .collect()
 }
 
+#[derive(Default)]
 struct MacroArgs {
     description: String,
+    // `(lang, text)` pairs from `description(en = "...", fr = "...")`
+    localized_descriptions: Vec<(String, String)>,
+    // Longer documentation; `description` is still what the model sees
+    long_description: Option<String>,
     name: Option<String>,
+    // Prefix joined to the name with `_`, e.g. `docs_search`
+    namespace: Option<String>,
+    read_only: bool,
+    idempotent: bool,
+    // Destructive tools that agent UIs should confirm before running
+    requires_confirmation: bool,
+    // Estimated cost per call (e.g. dollars) for budget-aware agents
+    cost: f64,
+    output_rename_all: Option<RenameRule>,
+    // Drop `null`-valued keys from the serialized output
+    output_skip_nulls: bool,
+    // Key the result is serialized under instead of "result"
+    result_key: Option<String>,
+    // Fallible context initializer used by the generated `try_new`
+    try_new: Option<Expr>,
+    // Record the serialized args on the call span (requires `Args: Serialize`)
+    trace_args: bool,
+    // Reject args with keys not declared in the schema
+    deny_unknown_fields: bool,
+    // Reject args whose serialized size exceeds this many bytes (requires `Args: Serialize`)
+    max_args_bytes: Option<Expr>,
+    // Check args against the full JSON schema (requires `Args: Serialize` and
+    // yart's `validate-schema` feature)
+    validate_schema: bool,
+    // `fn(&Output) -> Result<(), ToolError>` run on successful results
+    validate: Option<Expr>,
+    // `fn(&mut Args) -> Result<(), ToolError>` run before the body
+    before: Option<Expr>,
+    // `fn(&Output)` run after the body succeeds
+    after: Option<Expr>,
+    // `fn(Output) -> impl Serialize` applied before serialization
+    map_output: Option<Expr>,
+    // Args fields replaced with "***" when traced
+    secrets: Vec<String>,
+    // Run a non-async body on the blocking thread pool (the default for sync fns)
+    blocking: Option<bool>,
+    // Process-wide cap on concurrent calls of this tool
+    max_concurrency: Option<Expr>,
+    // Calls allowed per period (in seconds) from `rate_limit = "10/s"`
+    rate_limit: Option<(u32, u64)>,
+    // `fn(&Ctx) -> impl ToString` picking the rate limit bucket, e.g. the API key
+    rate_limit_key: Option<Expr>,
+    // Abort the body after this many milliseconds with a `Timeout` error
+    timeout_ms: Option<Expr>,
+    // Await the body in `call` instead of spawning it on its own task
+    inline: Option<bool>,
+    // Example args JSON from `#[yart(example = "...")]`, checked by a generated test
+    examples: Vec<String>,
+    // The body returns `impl Stream<Item = String>`; implements `yart::StreamingTool`
+    stream: bool,
+    // Generate `run_cli` and `run_cli_from`, parsing the args from command-line flags
+    cli: bool,
+    // Extra JSON (category, tags, cost, ...) returned by the generated `metadata()`
+    metadata: Option<String>,
+    // Deprecation note, e.g. "use new_search instead", appended to the description
+    deprecated: Option<String>,
+    // `async fn(&Ctx) -> Result<(), ToolError>` run once per instance before the first call
+    init: Option<Expr>,
+    // Return failures as an `{"error": ..., "kind": ...}` result instead of `Err`
+    errors_as_output: bool,
+    // Each `key = value` as written, so errors about it can point at it
+    written: Vec<(String, proc_macro2::TokenStream)>,
+}
+
+impl MacroArgs {
+    // An error spanned on `key = value`, or on `fallback` when the key wasn't written
+    fn error(&self, key: &str, fallback: proc_macro2::Span, message: &str) -> syn::Error {
+        match self.written.iter().find(|(written, _)| written == key) {
+            Some((_, tokens)) => syn::Error::new_spanned(tokens, message),
+            None => syn::Error::new(fallback, message),
+        }
+    }
+}
+
+fn lit_str(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => Some(lit_str.value()),
+        _ => None,
+    }
+}
+
+fn lit_bool(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(lit_bool),
+            ..
+        }) => Some(lit_bool.value),
+        _ => None,
+    }
+}
+
+// Parse `"10/s"` into 10 calls per 1 second; `m` and `h` are minutes and hours
+fn parse_rate_limit(spec: &str) -> Option<(u32, u64)> {
+    let (calls, unit) = spec.split_once('/')?;
+    let calls: u32 = calls.trim().parse().ok().filter(|calls| *calls > 0)?;
+    let period = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return None,
+    };
+    Some((calls, period))
 }
 
 impl Parse for MacroArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = MacroArgs::default();
         let mut description = None;
-        let mut name = None;
 
         if !input.is_empty() {
             let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(input)?;
             for meta in meta_list {
-                if let Meta::NameValue(nv) = meta {
-                    let ident = nv.path.get_ident().unwrap().to_string();
-                    if ident == "description" {
-                        if let Expr::Lit(ExprLit {
-                            lit: Lit::Str(lit_str),
-                            ..
-                        }) = nv.value
-                        {
-                            description = Some(lit_str.value());
+                // A bare flag such as `read_only` is shorthand for `read_only = true`
+                let written = meta.to_token_stream();
+                let (path, value): (_, Expr) = match meta {
+                    Meta::NameValue(nv) => (nv.path, nv.value),
+                    Meta::Path(path) => (path, parse_quote! { true }),
+                    Meta::List(list) if list.path.is_ident("description") => {
+                        let langs = list.parse_args_with(
+                            Punctuated::<MetaNameValue, Token![,]>::parse_terminated,
+                        )?;
+                        for nv in langs {
+                            let lang = nv.path.require_ident()?.to_string();
+                            let text = lit_str(&nv.value).ok_or_else(|| {
+                                syn::Error::new_spanned(
+                                    &nv.value,
+                                    "localized descriptions must be string literals",
+                                )
+                            })?;
+                            args.localized_descriptions.push((lang, text));
                         }
-                    } else if ident == "name" {
-                        if let Expr::Lit(ExprLit {
-                            lit: Lit::Str(lit_str),
-                            ..
-                        }) = nv.value
-                        {
-                            name = Some(lit_str.value());
+                        // `en` is what the model sees by default, falling back to the first
+                        description = args
+                            .localized_descriptions
+                            .iter()
+                            .find(|(lang, _)| lang == "en")
+                            .or(args.localized_descriptions.first())
+                            .map(|(_, text)| text.clone())
+                            .or(description);
+                        continue;
+                    }
+                    Meta::List(list) => {
+                        return Err(syn::Error::new_spanned(
+                            &list.path,
+                            format!(
+                                "unknown rig_tool attribute `{}`",
+                                list.path.to_token_stream()
+                            ),
+                        ))
+                    }
+                };
+                let ident = path.require_ident()?.to_string();
+                let value = &value;
+                args.written.push((ident.clone(), written));
+                match ident.as_str() {
+                    "description" => description = lit_str(value).or(description),
+                    "name" => args.name = lit_str(value).or(args.name),
+                    "namespace" => args.namespace = lit_str(value).or(args.namespace),
+                    "long_description" => {
+                        args.long_description = lit_str(value).or(args.long_description)
+                    }
+                    "read_only" => args.read_only = lit_bool(value).unwrap_or(args.read_only),
+                    "idempotent" => args.idempotent = lit_bool(value).unwrap_or(args.idempotent),
+                    "requires_confirmation" => {
+                        args.requires_confirmation =
+                            lit_bool(value).unwrap_or(args.requires_confirmation)
+                    }
+                    "output_skip_nulls" => {
+                        args.output_skip_nulls = lit_bool(value).unwrap_or(args.output_skip_nulls)
+                    }
+                    "output_rename_all" => {
+                        let rule = lit_str(value).ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                value,
+                                "output_rename_all must be a string literal",
+                            )
+                        })?;
+                        let rule = RenameRule::from_str(&rule)
+                            .map_err(|e| syn::Error::new_spanned(value, e))?;
+                        args.output_rename_all = Some(rule);
+                    }
+                    "blocking" => args.blocking = lit_bool(value).or(args.blocking),
+                    "max_concurrency" => args.max_concurrency = Some(value.clone()),
+                    "timeout_ms" => args.timeout_ms = Some(value.clone()),
+                    "inline" => args.inline = lit_bool(value).or(args.inline),
+                    "max_args_bytes" => args.max_args_bytes = Some(value.clone()),
+                    "result_key" => args.result_key = lit_str(value).or(args.result_key),
+                    "try_new" => args.try_new = Some(value.clone()),
+                    "init" => args.init = Some(value.clone()),
+                    "validate" => args.validate = Some(value.clone()),
+                    "before" => args.before = Some(value.clone()),
+                    "after" => args.after = Some(value.clone()),
+                    "map_output" => args.map_output = Some(value.clone()),
+                    "trace_args" => args.trace_args = lit_bool(value).unwrap_or(args.trace_args),
+                    "deny_unknown_fields" => {
+                        args.deny_unknown_fields =
+                            lit_bool(value).unwrap_or(args.deny_unknown_fields)
+                    }
+                    "metadata" => {
+                        let json = lit_str(value).ok_or_else(|| {
+                            syn::Error::new_spanned(value, "metadata must be a string literal")
+                        })?;
+                        if let Err(e) = serde_json::from_str::<serde_json::Value>(&json) {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                format!("metadata is not valid JSON: {}", e),
+                            ));
+                        }
+                        args.metadata = Some(json);
+                    }
+                    "rate_limit" => {
+                        let spec = lit_str(value).ok_or_else(|| {
+                            syn::Error::new_spanned(value, "rate_limit must be a string literal")
+                        })?;
+                        let limit = parse_rate_limit(&spec).ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                value,
+                                "rate_limit must look like \"10/s\", \"100/m\", or \"1000/h\"",
+                            )
+                        })?;
+                        args.rate_limit = Some(limit);
+                    }
+                    "cost" => {
+                        args.cost = match value {
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Float(f), ..
+                            }) => f.base10_parse()?,
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Int(i), ..
+                            }) => i.base10_parse::<u32>()?.into(),
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    value,
+                                    "cost must be a non-negative number, e.g. `cost = 0.002`",
+                                ))
+                            }
+                        };
+                    }
+                    "rate_limit_key" => args.rate_limit_key = Some(value.clone()),
+                    "deprecated" => args.deprecated = lit_str(value).or(args.deprecated),
+                    "errors_as_output" => {
+                        args.errors_as_output = lit_bool(value).unwrap_or(args.errors_as_output)
+                    }
+                    "stream" => args.stream = lit_bool(value).unwrap_or(args.stream),
+                    "cli" => args.cli = lit_bool(value).unwrap_or(args.cli),
+                    "validate_schema" => {
+                        args.validate_schema = lit_bool(value).unwrap_or(args.validate_schema)
+                    }
+                    "secrets" => {
+                        if let Expr::Array(array) = value {
+                            args.secrets = array.elems.iter().filter_map(lit_str).collect();
                         }
                     }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &path,
+                            format!("unknown rig_tool attribute `{}`", ident),
+                        ))
+                    }
                 }
             }
         }
 
-        Ok(MacroArgs {
-            description: description.expect("rig_tool requires a description attribute"),
-            name,
-        })
+        args.description = description.expect("rig_tool requires a description attribute");
+        Ok(args)
+    }
+}
+
+// Everything needed to generate a tool struct, independent of whether it wraps a
+// free function or a method on a service type.
+struct ToolSpec {
+    vis: Visibility,
+    // `#[cfg(...)]` attributes forwarded to every generated item
+    cfgs: Vec<Attribute>,
+    struct_name: Ident,
+    tool_name: String,
+    attrs: MacroArgs,
+    context: Option<Type>,
+    args: Option<Type>,
+    // Parameter patterns used by the body, e.g. `(db, http)` for a tuple context
+    ctx_pat: Pat,
+    args_pat: Pat,
+    return_ty: Type,
+    // Whether the body is async; sync bodies run without `.await`
+    is_async: bool,
+    // Generic parameters of the function, which may only appear in the context type
+    generics: Generics,
+    body: proc_macro2::TokenStream,
+}
+
+// Strip `(T)` and the invisible groups `macro_rules!` wraps around `$t:ty`
+fn ungroup(mut ty: &Type) -> &Type {
+    loop {
+        match ty {
+            Type::Paren(paren) => ty = &paren.elem,
+            Type::Group(group) => ty = &group.elem,
+            _ => return ty,
+        }
+    }
+}
+
+// Extract `T` from a `Result<T, E>` return type
+fn result_ok_type(output: &ReturnType) -> Type {
+    match output {
+        ReturnType::Type(_, ty) => {
+            if let Type::Path(type_path) = ungroup(ty) {
+                if let Some(result) = type_path.path.segments.last() {
+                    if result.ident == "Result" {
+                        if let syn::PathArguments::AngleBracketed(args) = &result.arguments {
+                            if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
+                                inner_ty.clone()
+                            } else {
+                                panic!("Expected Result<T, E> with type argument");
+                            }
+                        } else {
+                            panic!("Expected Result<T, E> with type arguments");
+                        }
+                    } else {
+                        panic!("Expected Result return type");
+                    }
+                } else {
+                    panic!("Expected Result return type");
+                }
+            } else {
+                panic!("Expected Result return type");
+            }
+        }
+        _ => panic!("rig_tool function must return Result"),
+    }
+}
+
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .cloned()
+        .collect()
+}
+
+// Remove `#[yart(example = "...")]` attributes, returning the example JSON strings
+fn take_examples(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<String>> {
+    let mut examples = Vec::new();
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("yart") {
+            return true;
+        }
+        let parsed = attr.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated);
+        match parsed {
+            Ok(metas) => examples.extend(
+                metas
+                    .iter()
+                    .filter(|nv| nv.path.is_ident("example"))
+                    .filter_map(|nv| lit_str(&nv.value)),
+            ),
+            Err(e) => result = Err(e),
+        }
+        false
+    });
+    result.map(|_| examples)
+}
+
+// Tool names accepted by the major providers: `^[a-zA-Z0-9_-]{1,64}$`
+fn is_valid_tool_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Span of the first borrow in `ty` that isn't `'static`, e.g. the `&` of `&str`
+fn borrowed_span(ty: &Type) -> Option<proc_macro2::Span> {
+    match ty {
+        Type::Reference(reference) => match &reference.lifetime {
+            Some(lifetime) if lifetime.ident == "static" => borrowed_span(&reference.elem),
+            _ => Some(reference.and_token.span),
+        },
+        Type::Path(type_path) => type_path.path.segments.iter().find_map(|segment| {
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Lifetime(lifetime) if lifetime.ident != "static" => {
+                    Some(lifetime.apostrophe)
+                }
+                syn::GenericArgument::Type(ty) => borrowed_span(ty),
+                _ => None,
+            })
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(borrowed_span),
+        Type::Array(array) => borrowed_span(&array.elem),
+        Type::Slice(slice) => borrowed_span(&slice.elem),
+        Type::Paren(paren) => borrowed_span(&paren.elem),
+        Type::Group(group) => borrowed_span(&group.elem),
+        _ => None,
     }
 }
 
+fn is_named_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+fn is_tool_output(ty: &Type) -> bool {
+    is_named_type(ty, "ToolOutput")
+}
+
+fn is_tool_output_stream(ty: &Type) -> bool {
+    is_named_type(ty, "ToolOutputStream")
+}
+
 #[proc_macro_attribute]
 pub fn rig_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(attr as MacroArgs);
-    let item = parse_macro_input!(item as ItemFn);
-
-    let description = args.description;
-    let name = args.name;
+    let mut attrs = parse_macro_input!(attr as MacroArgs);
+    let mut item = parse_macro_input!(item as ItemFn);
+    attrs.examples = match take_examples(&mut item.attrs) {
+        Ok(examples) => examples,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let vis = &item.vis;
     let fn_name = &item.sig.ident;
     // Convert function name to UpperCamelCase for struct name
-    let struct_name = syn::Ident::new(&to_upper_camel_case(&fn_name.to_string()), fn_name.span());
+    let struct_name = Ident::new(&to_upper_camel_case(&fn_name.to_string()), fn_name.span());
     // Use provided name or function name
-    let tool_name = name.unwrap_or_else(|| format!("{}", fn_name));
+    let tool_name = attrs.name.clone().unwrap_or_else(|| format!("{}", fn_name));
 
     // Extract inputs (context and args)
     let inputs = &item.sig.inputs;
+    if let Some(FnArg::Receiver(receiver)) = inputs.first() {
+        return syn::Error::new_spanned(
+            receiver,
+            "rig_tool on a method requires #[rig_tools] on the enclosing impl block",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let mut ctx_pat: Pat = parse_quote! { ctx };
+    let mut args_pat: Pat = parse_quote! { args };
     let (context, args) = match inputs.len() {
         0 => (None, None),
         1 => {
             let arg = inputs.first().unwrap();
             if let FnArg::Typed(pat_type) = arg {
                 // Assume single argument is args (no context)
-                (None, Some(pat_type.ty.clone()))
+                args_pat = *pat_type.pat.clone();
+                (None, Some(*pat_type.ty.clone()))
             } else {
                 panic!("Expected typed argument");
             }
@@ -97,8 +497,13 @@ pub fn rig_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
             let mut iter = inputs.iter();
             let ctx_arg = iter.next().unwrap();
             let args_arg = iter.next().unwrap();
-            if let (FnArg::Typed(ctx_pat), FnArg::Typed(args_pat)) = (ctx_arg, args_arg) {
-                (Some(ctx_pat.ty.clone()), Some(args_pat.ty.clone()))
+            if let (FnArg::Typed(ctx_pat_type), FnArg::Typed(args_pat_type)) = (ctx_arg, args_arg) {
+                ctx_pat = *ctx_pat_type.pat.clone();
+                args_pat = *args_pat_type.pat.clone();
+                (
+                    Some(*ctx_pat_type.ty.clone()),
+                    Some(*args_pat_type.ty.clone()),
+                )
             } else {
                 panic!("Expected typed arguments");
             }
@@ -106,39 +511,250 @@ pub fn rig_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
         _ => panic!("rig_tool expects 0-2 arguments (context and/or args)"),
     };
 
-    let args_ty = args
-        .as_ref()
-        .map_or_else(|| parse_quote! { () }, |ty| *ty.clone());
-    let ctx_ty = context
-        .as_ref()
-        .map_or_else(|| parse_quote! { () }, |ty| *ty.clone());
+    let fn_body = &item.block;
+    expand_tool(ToolSpec {
+        vis: item.vis.clone(),
+        cfgs: cfg_attrs(&item.attrs),
+        struct_name,
+        tool_name,
+        attrs,
+        context,
+        args,
+        ctx_pat,
+        args_pat,
+        return_ty: result_ok_type(&item.sig.output),
+        is_async: item.sig.asyncness.is_some(),
+        generics: item.sig.generics.clone(),
+        body: quote! { #fn_body },
+    })
+    .into()
+}
 
-    // Extract return type
-    let return_ty = match &item.sig.output {
-        ReturnType::Type(_, ty) => {
-            if let Type::Path(type_path) = &**ty {
-                if let Some(result) = type_path.path.segments.last() {
-                    if result.ident == "Result" {
-                        if let syn::PathArguments::AngleBracketed(args) = &result.arguments {
-                            if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                                inner_ty.clone()
-                            } else {
-                                panic!("Expected Result<T, E> with type argument");
-                            }
-                        } else {
-                            panic!("Expected Result<T, E> with type arguments");
-                        }
-                    } else {
-                        panic!("Expected Result return type");
-                    }
-                } else {
-                    panic!("Expected Result return type");
-                }
-            } else {
-                panic!("Expected Result return type");
+/// Generates tools from `#[rig_tool]`-annotated methods of an impl block.
+///
+/// Each annotated method takes `&self` and optionally an args value. The method
+/// stays on the impl, and a tool struct named after the method in UpperCamelCase
+/// (e.g. `search_docs` -> `SearchDocs`) is emitted next to it with the method's
+/// visibility. Sync methods run like sync `#[rig_tool]` functions. The tool's context
+/// is `Arc<Self>`, so it is constructed with `SearchDocs::new(Arc::new(service))`.
+#[proc_macro_attribute]
+pub fn rig_tools(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(item as ItemImpl);
+    let self_ty = &item.self_ty;
+    let mut tools = Vec::new();
+
+    for impl_item in item.items.iter_mut() {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+        let Some(index) = method.attrs.iter().position(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "rig_tool")
+        }) else {
+            continue;
+        };
+        let attr = method.attrs.remove(index);
+        let mut attrs = match attr.parse_args::<MacroArgs>() {
+            Ok(attrs) => attrs,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        attrs.examples = match take_examples(&mut method.attrs) {
+            Ok(examples) => examples,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
+        let method_name = &method.sig.ident;
+        let mut inputs = method.sig.inputs.iter();
+        match inputs.next() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {}
+            _ => {
+                return syn::Error::new_spanned(&method.sig, "rig_tool methods must take &self")
+                    .to_compile_error()
+                    .into()
+            }
+        }
+        let args_ty = match (inputs.next(), inputs.next()) {
+            (None, _) => None,
+            (Some(FnArg::Typed(pat_type)), None) => Some(*pat_type.ty.clone()),
+            _ => {
+                return syn::Error::new_spanned(
+                    &method.sig.inputs,
+                    "rig_tool methods expect &self and at most one args argument",
+                )
+                .to_compile_error()
+                .into()
+            }
+        };
+        let call_args = if args_ty.is_some() {
+            quote! { args }
+        } else {
+            quote! {}
+        };
+        let is_async = method.sig.asyncness.is_some();
+        let dot_await = if is_async {
+            quote! { .await }
+        } else {
+            quote! {}
+        };
+
+        tools.push(expand_tool(ToolSpec {
+            vis: method.vis.clone(),
+            cfgs: cfg_attrs(&method.attrs),
+            struct_name: Ident::new(
+                &to_upper_camel_case(&method_name.to_string()),
+                method_name.span(),
+            ),
+            tool_name: attrs
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{}", method_name)),
+            attrs,
+            context: Some(parse_quote! { std::sync::Arc<#self_ty> }),
+            args: args_ty,
+            ctx_pat: parse_quote! { ctx },
+            args_pat: parse_quote! { args },
+            return_ty: result_ok_type(&method.sig.output),
+            is_async,
+            generics: Generics::default(),
+            body: quote! { ctx.#method_name(#call_args)#dot_await.map_err(Into::into) },
+        }));
+    }
+
+    quote! {
+        #item
+        #(#tools)*
+    }
+    .into()
+}
+
+fn expand_tool(spec: ToolSpec) -> proc_macro2::TokenStream {
+    let ToolSpec {
+        vis,
+        cfgs,
+        struct_name,
+        tool_name,
+        attrs,
+        context,
+        args,
+        ctx_pat,
+        args_pat,
+        return_ty,
+        is_async,
+        generics,
+        body: fn_body,
+    } = spec;
+    let is_generic = !generics.params.is_empty();
+    if is_generic && context.is_none() {
+        return syn::Error::new_spanned(
+            &generics,
+            "generic parameters are only supported on the context type",
+        )
+        .to_compile_error();
+    }
+    // Attribute errors without a `key = value` to point at point at the function name
+    let fn_span = struct_name.span();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // The output is serialized after the body's task ends, so it can't borrow from it
+    if let Some(span) = borrowed_span(&return_ty) {
+        return syn::Error::new(
+            span,
+            "tool output must be 'static: return owned data (e.g. `String` instead of `&str`)",
+        )
+        .to_compile_error();
+    }
+    let short_name = tool_name;
+    let tool_name = match &attrs.namespace {
+        Some(namespace) => {
+            let name = format!("{}_{}", namespace, short_name);
+            if !is_valid_tool_name(&name) {
+                let message = format!(
+                    "namespaced tool name `{}` must be 1-64 characters of [a-zA-Z0-9_-]",
+                    name
+                );
+                return attrs
+                    .error("namespace", fn_span, &message)
+                    .to_compile_error();
+            }
+            name
+        }
+        None => short_name.clone(),
+    };
+    // Deprecated tools say so in every description the model can see
+    let deprecation_note = |text: &str| match &attrs.deprecated {
+        Some(note) => format!("{} (deprecated: {})", text, note),
+        None => text.to_string(),
+    };
+    let description = &deprecation_note(&attrs.description);
+    let long_description = attrs.long_description.as_ref().unwrap_or(description);
+    let deprecated = match &attrs.deprecated {
+        Some(note) => quote! { Some(#note) },
+        None => quote! { None },
+    };
+    let read_only = attrs.read_only;
+    let requires_confirmation = attrs.requires_confirmation;
+    let description_for = if attrs.localized_descriptions.is_empty() {
+        quote! {
+            let _ = lang;
+            Self::DESCRIPTION
+        }
+    } else {
+        let (langs, texts): (Vec<_>, Vec<_>) = attrs
+            .localized_descriptions
+            .iter()
+            .map(|(lang, text)| (lang.clone(), deprecation_note(text)))
+            .unzip();
+        quote! {
+            match lang {
+                #(#langs => #texts,)*
+                _ => Self::DESCRIPTION,
             }
         }
-        _ => panic!("rig_tool function must return Result"),
+    };
+    let idempotent = attrs.idempotent;
+    let requires_context = context.is_some();
+    let cost = attrs.cost;
+    // A nonzero cost is also reported in object metadata, unless it sets its own
+    let metadata_json = if cost > 0.0 {
+        let mut value = match &attrs.metadata {
+            Some(json) => serde_json::from_str(json).expect("metadata was checked while parsing"),
+            None => serde_json::json!({}),
+        };
+        if let Some(map) = value.as_object_mut() {
+            map.entry("cost").or_insert(serde_json::json!(cost));
+        }
+        Some(value.to_string())
+    } else {
+        attrs.metadata.clone()
+    };
+    let metadata = match &metadata_json {
+        Some(json) => quote! {
+            serde_json::from_str(#json).expect("metadata was checked at compile time")
+        },
+        None => quote! { serde_json::Value::Null },
+    };
+    let deny_unknown_fields = attrs.deny_unknown_fields;
+
+    let args_ty = args.clone().unwrap_or_else(|| parse_quote! { () });
+    let ctx_ty = context.clone().unwrap_or_else(|| parse_quote! { () });
+    // Bounding the rig impl on the args puts `Send + Sync` and `Deserialize` in scope
+    // for its body, so bad args fail only at the `assert_args` checks below. The
+    // `for<'a>` keeps the bound from being checked (and reported again) on the impl
+    let args_bound = quote_spanned! {args_ty.span()=>
+        for<'a> #args_ty: yart::SendArgs + yart::DeserializeArgs
+    };
+    // Likewise for cloning the context into each call, checked by `assert_context_clone`
+    let clone_context_bound = match &context {
+        Some(ctx_ty) if !is_generic => {
+            quote_spanned! {ctx_ty.span()=> for<'a> #ctx_ty: yart::CloneContext }
+        }
+        _ => quote! {},
+    };
+    let tool_bounds = quote! { #args_bound, #clone_context_bound };
+    let tool_where_clause = {
+        let predicates = where_clause.iter().flat_map(|w| w.predicates.iter());
+        quote! { where #(#predicates,)* #tool_bounds }
     };
 
     // Error type
@@ -146,108 +762,737 @@ pub fn rig_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     // Generate internal_call
     let internal_call_inputs = if context.is_some() && args.is_some() {
-        quote! { ctx: #ctx_ty, args: #args_ty }
+        quote! { #ctx_pat: #ctx_ty, #args_pat: #args_ty }
     } else if context.is_some() {
-        quote! { ctx: #ctx_ty }
+        quote! { #ctx_pat: #ctx_ty }
     } else if args.is_some() {
-        quote! { args: #args_ty }
+        quote! { #args_pat: #args_ty }
     } else {
         quote! {}
     };
 
-    let fn_body = &item.block;
-
     // Generate call method
-    let call_body = if context.is_some() && args.is_some() {
+    let call_args = if context.is_some() && args.is_some() {
+        quote! { ctx, args }
+    } else if context.is_some() {
+        quote! { ctx }
+    } else if args.is_some() {
+        quote! { args }
+    } else {
+        quote! {}
+    };
+    let clone_ctx = if context.is_some() {
+        quote! { let ctx = yart::clone_context(&self.ctx); }
+    } else {
+        quote! {}
+    };
+    let assert_args = quote_spanned! {args_ty.span()=>
+        let _ = yart::assert_args_deserialize::<#args_ty>;
+        let _ = yart::assert_send_args::<#args_ty>;
+    };
+    // Each call clones the context into its task
+    // Generic contexts can't be named outside the impl, so only `clone` checks them
+    let assert_context_clone = match &context {
+        Some(ctx_ty) if !is_generic => {
+            quote_spanned! {ctx_ty.span()=> let _ = yart::assert_context_clone::<#ctx_ty>; }
+        }
+        _ => quote! {},
+    };
+    let trace_args = if attrs.trace_args {
+        let secrets = &attrs.secrets;
         quote! {
-            let ctx = self.ctx.clone();
-            let result = yart::wrap_unsafe(move || async move {
-                #struct_name::internal_call(ctx, args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!(e.to_string()))
-            })
-            .await?;
-            let serialized_result = serde_json::to_value(result)
-                .map_err(|e| yart::ToolError(format!("Serialization error: {}", e)))?;
-            Ok(yart::ToolOutput {
-                result: serialized_result,
-            })
+            if !span.is_disabled() {
+                if let Ok(value) = serde_json::to_value(&args) {
+                    let value = yart::redact(value, &[#(#secrets),*]);
+                    span.record("args", yart::tracing::field::display(value));
+                }
+            }
         }
-    } else if context.is_some() {
+    } else {
+        quote! {}
+    };
+    let skip_nulls = if attrs.output_skip_nulls {
+        quote! { let serialized_result = yart::remove_nulls(serialized_result); }
+    } else {
+        quote! {}
+    };
+    let rename_output = match &attrs.output_rename_all {
+        Some(rule) => {
+            let variant = format_ident!("{}", format!("{:?}", rule));
+            quote! {
+                let serialized_result =
+                    yart::rename_keys(serialized_result, yart::RenameRule::#variant);
+            }
+        }
+        None => quote! {},
+    };
+    let with_result_key = match &attrs.result_key {
+        Some(key) => quote! { .with_result_key(#key) },
+        None => quote! {},
+    };
+    // Bodies that build their own ToolOutput (e.g. with content parts) are passed through
+    let is_stream = is_tool_output_stream(&return_ty);
+    let is_text_stream = attrs.stream;
+    if is_text_stream && (is_stream || is_tool_output(&return_ty)) {
+        let message = "stream requires a body returning `impl Stream<Item = String>`";
+        return attrs.error("stream", fn_span, message).to_compile_error();
+    }
+    // Text streams are collected into a `String` before anything sees the result
+    let output_ty: Type = if is_text_stream {
+        parse_quote! { String }
+    } else {
+        return_ty.clone()
+    };
+    let map_output = match &attrs.map_output {
+        Some(_) if is_tool_output(&return_ty) || is_stream => {
+            let message = "map_output can't be used with ToolOutput or ToolOutputStream results";
+            return attrs
+                .error("map_output", fn_span, message)
+                .to_compile_error();
+        }
+        Some(map) => quote! { let result = #map(result); },
+        None => quote! {},
+    };
+    // The schema describes the body's output, so skip outputs replaced by map_output
+    let validate_output_schema = if attrs.map_output.is_none() {
         quote! {
-            let ctx = self.ctx.clone();
-            let result = yart::wrap_unsafe(move || async move {
-                #struct_name::internal_call(ctx)
-                    .await
-                    .map_err(|e| anyhow::anyhow!(e.to_string()))
-            })
-            .await?;
-            let serialized_result = serde_json::to_value(result)
-                .map_err(|e| yart::ToolError(format!("Serialization error: {}", e)))?;
-            Ok(yart::ToolOutput {
-                result: serialized_result,
-            })
+            yart::__validate_output!(#output_ty, &serialized_result, self.tool_name());
         }
-    } else if args.is_some() {
+    } else {
+        quote! {}
+    };
+    let into_output = if is_tool_output(&return_ty) || is_stream {
+        quote! { Ok(result #with_result_key) }
+    } else {
         quote! {
-            let result = yart::wrap_unsafe(move || async move {
-                #struct_name::internal_call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!(e.to_string()))
-            })
-            .await?;
-            let serialized_result = serde_json::to_value(result)
-                .map_err(|e| yart::ToolError(format!("Serialization error: {}", e)))?;
-            Ok(yart::ToolOutput {
-                result: serialized_result,
-            })
+            #map_output
+            // A separate kind and event, so monitoring can tell an output type that
+            // can't be represented as JSON from a failure in the body
+            let serialized_result = serde_json::to_value(result).map_err(|e| {
+                yart::tracing::error!(tool = self.tool_name(), error = %e, "tool output serialization failed");
+                yart::ToolError::serialization(format!("Serialization error: {}", e))
+                    .with_tool(self.tool_name())
+            })?;
+            #validate_output_schema
+            #skip_nulls
+            #rename_output
+            Ok(yart::ToolOutput::new(serialized_result) #with_result_key)
+        }
+    };
+    let validate_output = match &attrs.validate {
+        Some(validator) => quote! {
+            #validator(&result).map_err(|e: yart::ToolError| e.with_tool(self.tool_name()))?;
+        },
+        None => quote! {},
+    };
+    // rig has already deserialized the args, so measure their serialized form
+    let check_args_size = match &attrs.max_args_bytes {
+        Some(limit) => quote! {
+            let size = serde_json::to_vec(&args)
+                .map_err(|e| yart::ToolError::serialization(e.to_string()).with_tool(self.tool_name()))?
+                .len();
+            if size > #limit {
+                return Err(yart::ToolError::validation(format!(
+                    "Args are {} bytes, exceeding the limit of {} bytes",
+                    size, #limit
+                ))
+                .with_tool(self.tool_name()));
+            }
+        },
+        None => quote! {},
+    };
+    // rig has already deserialized the args, so validate their serialized form
+    let validate_schema = if attrs.validate_schema {
+        quote! {
+            let value = serde_json::to_value(&args)
+                .map_err(|e| yart::ToolError::serialization(e.to_string()).with_tool(self.tool_name()))?;
+            static ARGS_VALIDATOR: std::sync::OnceLock<yart::SchemaValidator> =
+                std::sync::OnceLock::new();
+            let validator =
+                ARGS_VALIDATOR.get_or_init(|| yart::SchemaValidator::new(&Self::parameters_schema()));
+            yart::validate_schema(&value, validator)
+                .map_err(|e| e.with_tool(self.tool_name()))?;
+        }
+    } else {
+        quote! {}
+    };
+    let before_hook = match &attrs.before {
+        Some(before) => quote! {
+            let mut args = args;
+            #before(&mut args).map_err(|e: yart::ToolError| e.with_tool(self.tool_name()))?;
+        },
+        None => quote! {},
+    };
+    let after_hook = match &attrs.after {
+        Some(after) => quote! { #after(&result); },
+        None => quote! {},
+    };
+    if attrs.blocking == Some(true) && is_async {
+        let message = "blocking requires a non-async function";
+        return attrs.error("blocking", fn_span, message).to_compile_error();
+    }
+    // CPU-bound sync bodies would stall the async runtime, so offload them by default
+    let blocking = !is_async && attrs.blocking.unwrap_or(true);
+    if blocking && (is_stream || is_text_stream) {
+        let message = "stream bodies can't run on the blocking thread pool";
+        return attrs.error("blocking", fn_span, message).to_compile_error();
+    }
+    let dot_await = if is_async {
+        quote! { .await }
+    } else {
+        quote! {}
+    };
+    let asyncness = if is_async {
+        quote! { async }
+    } else {
+        quote! {}
+    };
+    // Streams are concatenated inside the spawned task, so they need not be `Sync`
+    let run_body = if is_stream {
+        quote! {
+            match Self::internal_call(#call_args)#dot_await {
+                Ok(stream) => stream.into_output().await,
+                Err(e) => Err(e),
+            }
+        }
+    } else if is_text_stream {
+        quote! {
+            match Self::internal_call(#call_args)#dot_await {
+                Ok(stream) => Ok(yart::collect_text(stream).await),
+                Err(e) => Err(e),
+            }
         }
     } else {
+        quote! { Self::internal_call(#call_args)#dot_await }
+    };
+    let spawn_body = if blocking {
+        if attrs.timeout_ms.is_some() {
+            let message = "timeout_ms requires an async body or blocking = false";
+            return attrs
+                .error("timeout_ms", fn_span, message)
+                .to_compile_error();
+        }
+        quote! { yart::wrap_blocking(move || Ok::<_, anyhow::Error>(#run_body)) }
+    } else if let Some(timeout_ms) = &attrs.timeout_ms {
+        if attrs.inline == Some(true) {
+            let message =
+                "timeout_ms runs the body on its own task, so it can't be combined with inline";
+            return attrs.error("inline", fn_span, message).to_compile_error();
+        }
         quote! {
-            let result = yart::wrap_unsafe(move || async move {
-                #struct_name::internal_call()
-                    .await
-                    .map_err(|e| anyhow::anyhow!(e.to_string()))
+            yart::wrap_unsafe_timeout(std::time::Duration::from_millis(#timeout_ms), move || async move {
+                Ok::<_, anyhow::Error>(#run_body)
             })
-            .await?;
-            let serialized_result = serde_json::to_value(result)
-                .map_err(|e| yart::ToolError(format!("Serialization error: {}", e)))?;
-            Ok(yart::ToolOutput {
-                result: serialized_result,
+        }
+    } else if attrs.inline == Some(true) {
+        // Skips the task and channel round trip of spawning. Opt-in, since the body's
+        // future then becomes part of rig's `Send + Sync` `call` future, and a panic
+        // in it unwinds into the caller.
+        quote! {
+            async move { Ok::<_, anyhow::Error>(#run_body) }
+        }
+    } else {
+        quote! {
+            yart::wrap_unsafe(move || async move {
+                Ok::<_, anyhow::Error>(#run_body)
             })
         }
     };
+    // Calls beyond the rate are rejected rather than queued, so the model can back off
+    let check_rate_limit = match attrs.rate_limit {
+        Some((calls, period)) => {
+            let key = match &attrs.rate_limit_key {
+                Some(key_fn) => {
+                    if context.is_none() {
+                        let message = "rate_limit_key requires a tool with a context argument";
+                        return attrs
+                            .error("rate_limit_key", fn_span, message)
+                            .to_compile_error();
+                    }
+                    quote! { #key_fn(&self.ctx).to_string() }
+                }
+                None => quote! { String::new() },
+            };
+            quote! {
+                static RATE_LIMITER: yart::RateLimiter =
+                    yart::RateLimiter::new(#calls, std::time::Duration::from_secs(#period));
+                RATE_LIMITER
+                    .check(&#key)
+                    .map_err(|e| e.with_tool(self.tool_name()))?;
+            }
+        }
+        None => {
+            if attrs.rate_limit_key.is_some() {
+                let message = "rate_limit_key requires rate_limit";
+                return attrs
+                    .error("rate_limit_key", fn_span, message)
+                    .to_compile_error();
+            }
+            quote! {}
+        }
+    };
+    // Calls beyond the limit wait for a permit
+    let acquire_permit = match &attrs.max_concurrency {
+        Some(limit) => quote! {
+            static SEMAPHORE: yart::Semaphore = yart::Semaphore::const_new(#limit);
+            let _permit = SEMAPHORE.acquire().await.map_err(|e| {
+                yart::ToolError::new(e.to_string()).with_tool(self.tool_name())
+            })?;
+        },
+        None => quote! {},
+    };
+    // A failed init leaves the cell empty, so the next call retries it
+    let (init_field, init_value, run_init) = match &attrs.init {
+        Some(init) => {
+            if context.is_none() {
+                let message = "init requires a tool with a context argument";
+                return attrs.error("init", fn_span, message).to_compile_error();
+            }
+            (
+                quote! {
+                    // Set once `init` has succeeded for this instance
+                    initialized: yart::OnceCell<()>,
+                },
+                quote! { initialized: yart::OnceCell::new(), },
+                quote! {
+                    self.initialized
+                        .get_or_try_init(|| #init(&self.ctx))
+                        .await
+                        .map_err(|e: yart::ToolError| e.with_tool(self.tool_name()))?;
+                },
+            )
+        }
+        None => (quote! {}, quote! {}, quote! {}),
+    };
+    let (new_request_id, request_id_field, attach_request_id) = if cfg!(feature = "request-id") {
+        (
+            quote! { let request_id = yart::new_request_id(); },
+            quote! { request_id = %request_id, },
+            quote! { let output = output.map_err(|e| e.with_request_id(request_id)); },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
+    };
+    // Metrics still count the call as failed
+    let errors_as_output = if attrs.errors_as_output {
+        quote! {
+            let output = output.or_else(|e| Ok(yart::ToolOutput::from_error(&e)));
+        }
+    } else {
+        quote! {}
+    };
+    // The serde attribute can't be seen from here, so `ToolArgs` vouches for it
+    let assert_deny_unknown_fields = if deny_unknown_fields {
+        if args.is_none() {
+            let message = "deny_unknown_fields requires a tool with an args argument";
+            return attrs
+                .error("deny_unknown_fields", fn_span, message)
+                .to_compile_error();
+        }
+        quote_spanned! {args_ty.span()=> let _ = yart::assert_denies_unknown_fields::<#args_ty>; }
+    } else {
+        quote! {}
+    };
+
+    let call_body = quote! {
+        if !self.is_enabled() {
+            return Err(yart::ToolError::new("tool disabled").with_tool(self.tool_name()));
+        }
+        #check_rate_limit
+        #acquire_permit
+        #check_args_size
+        #validate_schema
+        #run_init
+        #before_hook
+        #clone_ctx
+        // Keep the body's ToolError intact across the spawn boundary so the
+        // tool name can be attached to it below.
+        let result = #spawn_body
+        .await
+        .map_err(|e| yart::ToolError::from(e).with_tool(self.tool_name()))?
+        .map_err(|e| e.with_tool(self.tool_name()))?;
+        // `after` only sees results that passed `validate`
+        #validate_output
+        #after_hook
+        #into_output
+    };
 
     // Generate new method conditionally
     let new_method = if context.is_some() {
         quote! {
             pub fn new(ctx: #ctx_ty) -> Self {
-                Self { ctx }
+                Self {
+                    ctx,
+                    name: None,
+                    #init_value
+                }
+            }
+
+            /// Build the tool under `name` instead of `NAME`, e.g. per tenant.
+            pub fn with_name(ctx: #ctx_ty, name: impl Into<String>) -> Self {
+                Self {
+                    name: Some(name.into()),
+                    ..Self::new(ctx)
+                }
             }
         }
     } else {
         quote! {
             pub fn new() -> Self {
-                Self { ctx: () }
+                Self { ctx: (), name: None }
+            }
+
+            /// Build the tool under `name` instead of `NAME`, e.g. per tenant.
+            pub fn with_name(name: impl Into<String>) -> Self {
+                Self {
+                    ctx: (),
+                    name: Some(name.into()),
+                }
+            }
+        }
+    };
+
+    // Context-free tools can be built with `Default`
+    let default_impl = if context.is_none() {
+        quote! {
+            #(#cfgs)*
+            impl Default for #struct_name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let call_stream_method = if is_stream {
+        quote! {
+            /// Run the body and return its output stream without buffering it.
+            pub async fn call_stream(&self, args: #args_ty) -> Result<#return_ty, yart::ToolError>
+            where
+                #clone_context_bound
+            {
+                #clone_ctx
+                Self::internal_call(#call_args)#dot_await
+                    .map_err(|e| e.with_tool(self.tool_name()))
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let cli_methods = if attrs.cli {
+        let (ctx_param, new_tool) = if context.is_some() {
+            (quote! { ctx: #ctx_ty, }, quote! { Self::new(ctx) })
+        } else {
+            (quote! {}, quote! { Self::new() })
+        };
+        let ctx_arg = if context.is_some() {
+            quote! { ctx, }
+        } else {
+            quote! {}
+        };
+        quote! {
+            /// Parse `argv` (starting with the program name) as command-line flags,
+            /// one per args field, call the tool, and return its output as JSON.
+            pub async fn run_cli_from<I, T>(
+                #ctx_param
+                argv: I,
+            ) -> Result<serde_json::Value, yart::ToolError>
+            where
+                I: IntoIterator<Item = T>,
+                T: Into<std::ffi::OsString> + Clone,
+            {
+                let tool = #new_tool;
+                let args = yart::cli::parse(
+                    tool.tool_name(),
+                    Self::DESCRIPTION,
+                    &Self::parameters_schema(),
+                    argv,
+                )
+                .map_err(|e| yart::ToolError::validation(e.to_string()).with_tool(tool.tool_name()))?;
+                let args: #args_ty = yart::deserialize_args(args)
+                    .map_err(|e| e.with_tool(tool.tool_name()))?;
+                let output = rig::tool::Tool::call(&tool, args).await?;
+                serde_json::to_value(output).map_err(|e| {
+                    yart::ToolError::serialization(format!("Serialization error: {}", e))
+                        .with_tool(tool.tool_name())
+                })
+            }
+
+            /// Run the tool from the process's command line and print its output as
+            /// JSON. `--help` and malformed flags print clap's message and exit.
+            pub async fn run_cli(#ctx_param) -> Result<(), yart::ToolError> {
+                let argv: Vec<std::ffi::OsString> = std::env::args_os().collect();
+                if let Err(e) = yart::cli::parse(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    &Self::parameters_schema(),
+                    argv.clone(),
+                ) {
+                    e.exit();
+                }
+                let output = Self::run_cli_from(#ctx_arg argv).await?;
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+                );
+                Ok(())
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let streaming_tool_impl = if is_text_stream {
+        quote! {
+            #(#cfgs)*
+            impl #impl_generics yart::StreamingTool for #struct_name #ty_generics #tool_where_clause {
+                async fn call_streaming(
+                    &self,
+                    args: #args_ty,
+                ) -> Result<yart::TextStream, yart::ToolError> {
+                    #clone_ctx
+                    let stream = Self::internal_call(#call_args)#dot_await
+                        .map_err(|e| e.with_tool(self.tool_name()))?;
+                    Ok(Box::pin(stream))
+                }
             }
         }
+    } else {
+        quote! {}
+    };
+
+    let try_new_method = match &attrs.try_new {
+        Some(init) => {
+            if context.is_none() {
+                let message = "try_new requires a tool with a context argument";
+                return attrs.error("try_new", fn_span, message).to_compile_error();
+            }
+            quote! {
+                /// Build the context with the tool's fallible initializer.
+                pub fn try_new() -> Result<Self, yart::ToolError> {
+                    Ok(Self::new(#init()?))
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    let examples = &attrs.examples;
+    // Examples can't be deserialized at compile time, so check them in a test
+    let examples_test = if examples.is_empty() {
+        quote! {}
+    } else if is_generic {
+        return syn::Error::new_spanned(
+            &generics,
+            "examples aren't supported on tools with generic parameters",
+        )
+        .to_compile_error();
+    } else {
+        let test_name = format_ident!("{}_examples_match_args", struct_name);
+        quote! {
+            #(#cfgs)*
+            #[cfg(test)]
+            #[test]
+            #[allow(non_snake_case)]
+            fn #test_name() {
+                if let Err(e) = #struct_name::check_examples() {
+                    panic!("{}", e);
+                }
+            }
+        }
+    };
+
+    // A descriptor needs a concrete type, so generic tools aren't registered
+    let register_descriptor = if cfg!(feature = "registry") && !is_generic {
+        let constructor = if context.is_none() {
+            // Probed, so args failing their bounds are only reported at the signature
+            quote! {
+                Some(|| {
+                    use yart::registry::{IsDynTool as _, NotDynTool as _};
+                    (&yart::registry::DynToolProbe::new(#struct_name::new)).build()
+                })
+            }
+        } else {
+            quote! { None }
+        };
+        quote! {
+            #(#cfgs)*
+            yart::registry::inventory::submit! {
+                yart::ToolDescriptor {
+                    name: #tool_name,
+                    description: #description,
+                    parameters: #struct_name::parameters_schema,
+                    constructor: #constructor,
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let parameters_schema = if deny_unknown_fields {
+        quote! {
+            let mut schema = yart::derive_parameters::<#args_ty>();
+            schema["additionalProperties"] = serde_json::Value::Bool(false);
+            schema
+        }
+    } else {
+        quote! { yart::derive_parameters::<#args_ty>() }
+    };
+
+    // Private functions still get a public struct, so tools can be built anywhere
+    let struct_vis = match vis {
+        Visibility::Inherited => quote! { pub },
+        vis => quote! { #vis },
     };
 
     // Generate struct and impls
-    let output = quote! {
-        #vis pub struct #struct_name {
+    quote! {
+        #(#cfgs)*
+        #struct_vis struct #struct_name #impl_generics #where_clause {
             ctx: #ctx_ty,
+            // Runtime override of `NAME`, set by `with_name`
+            name: Option<String>,
+            #init_field
         }
 
-        impl #struct_name {
+        #(#cfgs)*
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// The tool name reported to the model.
+            pub const NAME: &'static str = #tool_name;
+            /// The name without its namespace; equal to `NAME` when there is none.
+            pub const SHORT_NAME: &'static str = #short_name;
+            /// The tool description reported to the model.
+            pub const DESCRIPTION: &'static str = #description;
+            /// Extended documentation, defaulting to `DESCRIPTION`.
+            pub const LONG_DESCRIPTION: &'static str = #long_description;
+            /// Whether the tool only reads state, making it safe to retry or run in parallel.
+            pub const READ_ONLY: bool = #read_only;
+            /// Whether repeated calls with the same args have the same effect as one call.
+            pub const IDEMPOTENT: bool = #idempotent;
+            /// Whether the tool is destructive and a user should confirm each call.
+            pub const REQUIRES_CONFIRMATION: bool = #requires_confirmation;
+            /// Why the tool is deprecated and what replaces it, if it is.
+            pub const DEPRECATED: Option<&'static str> = #deprecated;
+            /// Whether the tool is built with `new(ctx)` rather than `new()`.
+            pub const REQUIRES_CONTEXT: bool = #requires_context;
+            /// Estimated cost of one call (e.g. in dollars), `0.0` when not declared.
+            pub const COST: f64 = #cost;
+
+            /// Example args JSON, each checked against `Args` by a generated test.
+            pub const EXAMPLES: &'static [&'static str] = &[#(#examples),*];
+
+            /// Extra JSON from the `metadata` attribute, or `null` when there is none.
+            pub fn metadata() -> serde_json::Value {
+                #metadata
+            }
+
+            /// The description in `lang` (e.g. `"fr"`), falling back to `DESCRIPTION`.
+            pub fn description_for(lang: &str) -> &'static str {
+                #description_for
+            }
+
             #new_method
 
-            async fn internal_call(#internal_call_inputs) -> Result<#return_ty, #error_ty> {
+            // The runtime name if one was given, else `NAME`
+            fn tool_name(&self) -> &str {
+                self.name.as_deref().unwrap_or(Self::NAME)
+            }
+
+            /// Whether the tool is switched on in `yart::enabled_tools()`; a disabled
+            /// tool fails every call with "tool disabled".
+            pub fn is_enabled(&self) -> bool {
+                yart::enabled_tools().is_enabled(self.tool_name())
+            }
+
+            #try_new_method
+
+            #call_stream_method
+
+            #cli_methods
+
+            /// JSON schema of the tool's output, available when the output type
+            /// implements `JsonSchema`.
+            pub fn output_schema() -> serde_json::Value
+            where
+                // Higher-ranked so the bound is only checked where this is called
+                for<'a> #output_ty: yart::JsonSchema,
+            {
+                yart::derive_output_schema::<#output_ty>()
+            }
+
+            /// Check that every entry of `EXAMPLES` deserializes into the tool's args.
+            pub fn check_examples() -> Result<(), yart::ToolError>
+            where
+                #args_bound
+            {
+                for example in Self::EXAMPLES {
+                    serde_json::from_str::<#args_ty>(example).map_err(|e| {
+                        yart::ToolError::validation(format!("Invalid example {}: {}", example, e))
+                            .with_tool(Self::NAME)
+                    })?;
+                }
+                Ok(())
+            }
+
+            /// The tool definition, available without an async runtime.
+            pub fn definition_sync(&self) -> rig::completion::ToolDefinition {
+                yart::tool_definition(self.tool_name(), Self::DESCRIPTION, Self::parameters_schema())
+            }
+
+            /// Add the tool to `builder`, e.g. `SearchTool::new(ctx).register(agent_builder)`.
+            pub fn register<B: yart::ToolBuilder>(self, builder: B) -> B
+            where
+                #tool_bounds
+            {
+                builder.tool(self)
+            }
+
+            /// JSON schema of the tool's arguments, computed once and cached.
+            pub fn parameters_schema() -> serde_json::Value {
+                static SCHEMA: std::sync::OnceLock<serde_json::Value> = std::sync::OnceLock::new();
+                SCHEMA.get_or_init(|| { #parameters_schema }).clone()
+            }
+
+            // Context and args are always passed, even when the body ignores them
+            #[allow(unused_variables)]
+            #asyncness fn internal_call(#internal_call_inputs) -> Result<#return_ty, #error_ty> {
                 #fn_body
             }
         }
 
-        impl rig::tool::Tool for #struct_name {
+        #default_impl
+
+        #(#cfgs)*
+        const _: () = {
+            #assert_args
+            let _ = yart::assert_send_static::<#output_ty>;
+            #assert_context_clone
+            #assert_deny_unknown_fields
+        };
+
+        #examples_test
+
+        #register_descriptor
+
+        #streaming_tool_impl
+
+        #(#cfgs)*
+        impl #impl_generics yart::ToolMetadata for #struct_name #ty_generics #where_clause {
+            const NAME: &'static str = #tool_name;
+            const DESCRIPTION: &'static str = #description;
+            const DENY_UNKNOWN_FIELDS: bool = #deny_unknown_fields;
+
+            fn parameters_schema() -> serde_json::Value {
+                Self::parameters_schema()
+            }
+        }
+
+        #(#cfgs)*
+        impl #impl_generics rig::tool::Tool for #struct_name #ty_generics #tool_where_clause {
             const NAME: &'static str = #tool_name;
 
             type Error = yart::ToolError;
@@ -255,22 +1500,303 @@ pub fn rig_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
             type Output = yart::ToolOutput;
 
             fn name(&self) -> String {
-                Self::NAME.to_string()
+                self.tool_name().to_string()
             }
 
             async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
-                rig::completion::ToolDefinition {
-                    name: Self::NAME.to_string(),
-                    description: #description.to_string(),
-                    parameters: yart::derive_parameters::<#args_ty>(),
-                }
+                self.definition_sync()
             }
 
             async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-                #call_body
+                use yart::tracing::Instrument as _;
+                #new_request_id
+                let span = yart::tracing::info_span!(
+                    "tool_call",
+                    tool = self.tool_name(),
+                    #request_id_field
+                    args = yart::tracing::field::Empty
+                );
+                #trace_args
+                let started = std::time::Instant::now();
+                let output: Result<Self::Output, Self::Error> =
+                    async { #call_body }.instrument(span).await;
+                #attach_request_id
+                yart::metrics::record(self.tool_name(), started.elapsed(), output.is_ok());
+                #errors_as_output
+                output
             }
         }
+    }
+}
+
+// Input of `tool!`: the wrapped function, the types its signature can't be read
+// for, and the usual `rig_tool` attributes.
+struct ToolInput {
+    func: syn::Path,
+    context: Option<Type>,
+    args: Option<Type>,
+    output: Type,
+    attrs: MacroArgs,
+}
+
+impl Parse for ToolInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let func: syn::Path = input.parse()?;
+        let (mut context, mut args, mut output) = (None, None, None);
+        let mut rest = Punctuated::<Meta, Token![,]>::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let fork = input.fork();
+            let key: Ident = fork.parse()?;
+            let slot = match key.to_string().as_str() {
+                "context" => &mut context,
+                "args" => &mut args,
+                "output" => &mut output,
+                _ => {
+                    rest.push(input.parse()?);
+                    continue;
+                }
+            };
+            input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            *slot = Some(input.parse::<Type>()?);
+        }
+        let output = output
+            .ok_or_else(|| syn::Error::new_spanned(&func, "tool! requires `output = Type`"))?;
+        Ok(ToolInput {
+            func,
+            context,
+            args,
+            output,
+            attrs: syn::parse2(quote! { #rest })?,
+        })
+    }
+}
+
+/// Generates a tool wrapping an existing async function that can't be annotated,
+/// e.g. one from another crate.
+///
+/// The signature is declared alongside the `rig_tool` attributes:
+/// `tool!(other::lookup, context = Db, args = LookupArgs, output = String, description = "...")`.
+/// `context` and `args` are optional and passed in that order; the function must
+/// return `Result<Output, E>` with `E: Into<ToolError>`. The struct is named after
+/// the tool name in UpperCamelCase.
+#[proc_macro]
+pub fn tool(input: TokenStream) -> TokenStream {
+    let ToolInput {
+        func,
+        context,
+        args,
+        output,
+        attrs,
+    } = parse_macro_input!(input as ToolInput);
+    let fn_name = &func
+        .segments
+        .last()
+        .expect("tool! requires a function path")
+        .ident;
+    let tool_name = attrs.name.clone().unwrap_or_else(|| fn_name.to_string());
+    let struct_name = Ident::new(&to_upper_camel_case(&tool_name), fn_name.span());
+    let call_args = match (&context, &args) {
+        (Some(_), Some(_)) => quote! { ctx, args },
+        (Some(_), None) => quote! { ctx },
+        (None, Some(_)) => quote! { args },
+        (None, None) => quote! {},
     };
+    expand_tool(ToolSpec {
+        vis: parse_quote! {},
+        cfgs: Vec::new(),
+        struct_name,
+        tool_name,
+        attrs,
+        context,
+        args,
+        ctx_pat: parse_quote! { ctx },
+        args_pat: parse_quote! { args },
+        return_ty: output,
+        is_async: true,
+        generics: Generics::default(),
+        body: quote! { #func(#call_args).await.map_err(Into::into) },
+    })
+    .into()
+}
+
+/// Derives `JsonSchema` for an args struct, with field descriptions declared as
+/// `#[tool(description = "...")]` instead of doc comments.
+///
+/// `#[tool(default = "...")]` declares a field's schema default as JSON, for
+/// defaults computed by a `#[serde(default = "...")]` function that the schema
+/// can't evaluate (e.g. a non-`Serialize` field type) or shouldn't (e.g. the time).
+///
+/// The schema comes from a hidden copy of the struct deriving `JsonSchema`, so
+/// `serde` and `schemars` attributes behave as with a plain derive. With
+/// `#[serde(deny_unknown_fields)]` it also implements `yart::DenyUnknownFields`.
+#[proc_macro_derive(ToolArgs, attributes(tool))]
+pub fn derive_tool_args(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    match expand_tool_args(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// Attributes the schema derive reads, copied onto the hidden struct
+fn is_schema_attr(attr: &Attribute) -> bool {
+    ["serde", "schemars", "doc"]
+        .iter()
+        .any(|name| attr.path().is_ident(name))
+}
+
+// Whether `serde` or `schemars` attributes set `key`, e.g. `rename` or `default`
+fn has_serde_key(attrs: &[Attribute], key: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde") || attr.path().is_ident("schemars"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= meta.path.is_ident(key);
+                // Skip the value of `key = value` entries
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                }
+                Ok(())
+            });
+            found
+        })
+}
+
+fn expand_tool_args(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ToolArgs can only be derived for structs",
+        ));
+    };
+    let ident = &input.ident;
+    let shadow = format_ident!("__ToolArgs{}", ident);
+    let attrs: Vec<&Attribute> = input.attrs.iter().filter(|a| is_schema_attr(a)).collect();
+    // Keep the original name unless the struct already renames itself
+    let rename = if has_serde_key(&input.attrs, "rename") {
+        quote! {}
+    } else {
+        let name = ident.to_string();
+        quote! { #[schemars(rename = #name)] }
+    };
+
+    let container_default = has_serde_key(&input.attrs, "default");
+    let mut default_fns = Vec::new();
+    let mut fields = data.fields;
+    for (i, field) in fields.iter_mut().enumerate() {
+        let mut description = None;
+        let mut default = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("tool")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("description") {
+                    description = Some(meta.value()?.parse::<syn::LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    let json = meta.value()?.parse::<syn::LitStr>()?;
+                    if let Err(e) = serde_json::from_str::<serde_json::Value>(&json.value()) {
+                        return Err(syn::Error::new_spanned(
+                            &json,
+                            format!("default is not valid JSON: {}", e),
+                        ));
+                    }
+                    default = Some(json);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown tool attribute, expected `description` or `default`"))
+                }
+            })?;
+        }
+        field.attrs.retain(is_schema_attr);
+        if let Some(description) = description {
+            field
+                .attrs
+                .push(parse_quote! { #[schemars(description = #description)] });
+        }
+        // A schema default on a field serde requires would promise more than it accepts
+        if let Some(json) = default {
+            if !container_default && !has_serde_key(&field.attrs, "default") {
+                return Err(syn::Error::new_spanned(
+                    &json,
+                    "tool(default) requires `#[serde(default)]` or `#[serde(default = \"...\")]` on the field",
+                ));
+            }
+            let default_fn = format_ident!("__tool_args_default_{}", i);
+            let default_path = default_fn.to_string();
+            default_fns.push(quote! {
+                fn #default_fn() -> serde_json::Value {
+                    serde_json::from_str(#json).expect("default was checked at compile time")
+                }
+            });
+            field
+                .attrs
+                .push(parse_quote! { #[schemars(default = #default_path)] });
+        }
+    }
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let params = &generics.params;
+    let shadow_struct = match &fields {
+        syn::Fields::Named(_) => quote! { struct #shadow<#params> #where_clause #fields },
+        syn::Fields::Unnamed(_) => quote! { struct #shadow<#params> #fields #where_clause; },
+        syn::Fields::Unit => quote! { struct #shadow; },
+    };
+    // The hidden struct's derive adds the `JsonSchema` bounds its fields need
+    let mut bounded = generics.clone();
+    bounded
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! { #shadow #ty_generics: yart::JsonSchema });
+    let impl_where = &bounded.where_clause;
+    // Lets `rig_tool(deny_unknown_fields)` check the serde attribute at compile time;
+    // a `schemars` one only changes the schema, not what serde accepts
+    let serde_attrs: Vec<Attribute> = (input.attrs.iter())
+        .filter(|attr| attr.path().is_ident("serde"))
+        .cloned()
+        .collect();
+    let deny_unknown_fields = if has_serde_key(&serde_attrs, "deny_unknown_fields") {
+        quote! { impl #impl_generics yart::DenyUnknownFields for #ident #ty_generics #where_clause {} }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        const _: () = {
+            #[derive(yart::JsonSchema)]
+            #[schemars(crate = "yart::schemars")]
+            #rename
+            #(#attrs)*
+            #[allow(dead_code)]
+            #shadow_struct
+
+            #(#default_fns)*
+
+            impl #impl_generics yart::JsonSchema for #ident #ty_generics #impl_where {
+                fn schema_name() -> String {
+                    <#shadow #ty_generics as yart::JsonSchema>::schema_name()
+                }
+
+                fn schema_id() -> std::borrow::Cow<'static, str> {
+                    <#shadow #ty_generics as yart::JsonSchema>::schema_id()
+                }
+
+                fn is_referenceable() -> bool {
+                    <#shadow #ty_generics as yart::JsonSchema>::is_referenceable()
+                }
+
+                fn json_schema(
+                    generator: &mut yart::schemars::gen::SchemaGenerator,
+                ) -> yart::schemars::schema::Schema {
+                    <#shadow #ty_generics as yart::JsonSchema>::json_schema(generator)
+                }
+            }
 
-    output.into()
+            #deny_unknown_fields
+        };
+    })
 }
@@ -3,7 +3,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
-use yart::ToolError;
+use yart::{Content, ToolError, ToolOutput};
 
 // Mock context and types
 #[derive(Clone)]
@@ -162,295 +162,2604 @@ async fn optional_tool(
     })
 }
 
+// Test function marked as read-only
+#[yart::rig_tool(
+    name = "read_only_tool",
+    description = "A tool that only reads",
+    read_only = true,
+    idempotent = true
+)]
+async fn read_only_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function with a destructive effect
+#[yart::rig_tool(description = "Deletes a file", requires_confirmation)]
+async fn delete_file_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function building its own multimodal output
+#[yart::rig_tool(description = "Renders a chart")]
+async fn render_chart(args: TestArgs) -> anyhow::Result<ToolOutput, ToolError> {
+    Ok(
+        ToolOutput::new(json!({ "title": args.input })).with_content(Content::Image {
+            mime: "image/png".to_string(),
+            data_base64: "iVBORw0KGgo=".to_string(),
+        }),
+    )
+}
+
+// Test function that only exists when its feature is enabled
+#[yart::rig_tool(name = "gated_tool", description = "A feature-gated tool")]
+#[cfg(feature = "test-gated-tool")]
+async fn gated_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Would be a duplicate definition if the cfg above were not forwarded to the generated struct
+#[cfg(not(feature = "test-gated-tool"))]
+pub struct GatedTool;
+
+// Test function with a parenthesized return type
+#[yart::rig_tool(description = "A tool with a parenthesized return type")]
+async fn paren_return(args: TestArgs) -> (anyhow::Result<TestOutput, ToolError>) {
+    Ok(TestOutput { result: args.input })
+}
+
+// Tool declared through macro_rules!, which wraps `$ret` in an invisible group
+macro_rules! grouped_tool {
+    ($ret:ty) => {
+        #[yart::rig_tool(description = "A tool with a grouped return type")]
+        async fn grouped_return(args: TestArgs) -> $ret {
+            Ok(TestOutput { result: args.input })
+        }
+    };
+}
+
+grouped_tool!(anyhow::Result<TestOutput, ToolError>);
+
+// Output struct using snake_case keys
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct ProfileOutput {
+    user_name: String,
+    created_at: u64,
+    home_address: Address,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct Address {
+    street_name: String,
+}
+
+// Test function whose output keys are renamed to camelCase
+#[yart::rig_tool(description = "Returns a profile", output_rename_all = "camelCase")]
+async fn camel_profile(args: TestArgs) -> anyhow::Result<ProfileOutput, ToolError> {
+    Ok(ProfileOutput {
+        user_name: args.input,
+        created_at: 1,
+        home_address: Address {
+            street_name: "Main".to_string(),
+        },
+    })
+}
+
+// Test function whose calls are recorded by the metrics test
+#[yart::rig_tool(description = "A tool with recorded metrics")]
+async fn metered_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    if args.input.is_empty() {
+        return Err(ToolError::new("Input cannot be empty"));
+    }
+    Ok(TestOutput { result: args.input })
+}
+
+// Separate shared resources passed as a tuple context
+pub struct Db {
+    table: String,
+}
+
+pub struct Http {
+    host: String,
+}
+
+// Test function destructuring a tuple context
+#[yart::rig_tool(description = "A tool with two context dependencies")]
+async fn tuple_context(
+    (db, http): (Arc<Db>, Arc<Http>),
+    args: TestArgs,
+) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput {
+        result: format!("{}@{}: {}", db.table, http.host, args.input),
+    })
+}
+
+fn open_test_context() -> anyhow::Result<Arc<TestContext>> {
+    Ok(Arc::new(TestContext {
+        value: "opened".to_string(),
+    }))
+}
+
+fn fail_test_context() -> anyhow::Result<Arc<TestContext>, ToolError> {
+    Err(ToolError::new("Connection refused"))
+}
+
+// Test functions whose context is built by a fallible initializer
+#[yart::rig_tool(description = "A tool with a fallible context", try_new = open_test_context)]
+async fn fallible_context(
+    ctx: Arc<TestContext>,
+    args: TestArgs,
+) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput {
+        result: format!("{}: {}", ctx.value, args.input),
+    })
+}
+
+#[yart::rig_tool(description = "A tool whose context fails", try_new = fail_test_context)]
+async fn failing_context(
+    ctx: Arc<TestContext>,
+    args: TestArgs,
+) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput {
+        result: format!("{}: {}", ctx.value, args.input),
+    })
+}
+
+// Args carrying a credential that must not reach the logs
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct SecretArgs {
+    query: String,
+    api_key: String,
+}
+
+// Test function whose args are traced with the api key redacted
+#[yart::rig_tool(
+    description = "A tool with secret args",
+    trace_args = true,
+    secrets = ["api_key"]
+)]
+async fn secret_tool(args: SecretArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.query })
+}
+
+fn reject_empty(output: &TestOutput) -> Result<(), ToolError> {
+    if output.result.is_empty() {
+        return Err(ToolError::new("Result must not be empty"));
+    }
+    Ok(())
+}
+
+// Test function whose output is validated before serialization
+#[yart::rig_tool(description = "A tool with a validated result", validate = reject_empty)]
+async fn validated_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+#[derive(Deserialize, Serialize, yart::ToolArgs)]
+#[serde(deny_unknown_fields)]
+pub struct StrictArgs {
+    input: String,
+}
+
+// Test function rejecting args keys that aren't in its schema
+#[yart::rig_tool(description = "A strict tool", deny_unknown_fields)]
+async fn strict_tool(args: StrictArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function with a separate long description
+#[yart::rig_tool(
+    description = "Searches documents",
+    long_description = "Searches the document index by keyword and returns the best match. \
+                        Results are ranked by recency."
+)]
+async fn documented_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function that may have nothing to return
+#[yart::rig_tool(description = "Looks up an optional value")]
+async fn maybe_tool(args: TestArgs) -> anyhow::Result<Option<TestOutput>, ToolError> {
+    if args.input.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(TestOutput { result: args.input }))
+}
+
+fn normalize_input(args: &mut TestArgs) -> Result<(), ToolError> {
+    if args.input.trim().is_empty() {
+        return Err(ToolError::validation("Input cannot be blank"));
+    }
+    args.input = args.input.trim().to_lowercase();
+    Ok(())
+}
+
+static OBSERVED: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+fn observe_output(output: &TestOutput) {
+    OBSERVED.lock().unwrap().push(output.result.clone());
+}
+
+// Test function with hooks around its body
+#[yart::rig_tool(
+    description = "A tool with before and after hooks",
+    before = normalize_input,
+    after = observe_output
+)]
+async fn hooked_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+static AUDITED: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+fn audit_output(output: &TestOutput) {
+    AUDITED.lock().unwrap().push(output.result.clone());
+}
+
+// Test function with both a validated result and an after hook
+#[yart::rig_tool(
+    description = "A tool auditing validated results",
+    validate = reject_empty,
+    after = audit_output
+)]
+async fn audited_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct PageArgs {
+    #[schemars(range(min = 1, max = 100))]
+    limit: u32,
+}
+
+// Test function whose args are checked against the full schema
+#[yart::rig_tool(description = "Lists a page of results", validate_schema)]
+async fn paged_tool(args: PageArgs) -> anyhow::Result<u32, ToolError> {
+    Ok(args.limit)
+}
+
+static NOTIFIED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+// Test function with neither args nor a result, run only for its side effect
+#[yart::rig_tool(description = "Sends a notification")]
+async fn notify_tool() -> anyhow::Result<(), ToolError> {
+    NOTIFIED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Ok(())
+}
+
+// Test function whose result is nested under a custom key
+#[yart::rig_tool(description = "Fetches data", result_key = "data")]
+async fn keyed_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function taking free-form key/value args
+#[yart::rig_tool(description = "Sets config values")]
+async fn set_config_tool(
+    args: std::collections::HashMap<String, Value>,
+) -> anyhow::Result<Vec<String>, ToolError> {
+    let mut keys: Vec<String> = args
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    keys.sort();
+    Ok(keys)
+}
+
+// Test function with descriptions in several languages
+#[yart::rig_tool(description(fr = "Traduit le texte", en = "Translates text"))]
+async fn translate_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function with example args, checked by a generated test
+#[yart::rig_tool(description = "Echoes the example input")]
+#[yart(example = r#"{"input":"hi"}"#)]
+#[yart(example = r#"{"input":"hello, world"}"#)]
+async fn example_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+// Test function streaming its output in chunks
+#[yart::rig_tool(description = "Reads a large file")]
+async fn read_file_tool(args: TestArgs) -> anyhow::Result<yart::ToolOutputStream, ToolError> {
+    let chunks = ["header,".to_string(), args.input, ",footer".to_string()];
+    Ok(yart::ToolOutputStream::new(futures::stream::iter(
+        chunks.map(|chunk| Ok(yart::Bytes::from(chunk))),
+    )))
+}
+
+static ACTIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static MAX_ACTIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+// Test function backed by a rate-limited API
+#[yart::rig_tool(description = "Calls a rate-limited API", max_concurrency = 2)]
+async fn limited_tool() -> anyhow::Result<(), ToolError> {
+    use std::sync::atomic::Ordering;
+    let active = ACTIVE.fetch_add(1, Ordering::SeqCst) + 1;
+    MAX_ACTIVE.fetch_max(active, Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    Ok(())
+}
+
+// Test function whose name is prefixed with a namespace
+#[yart::rig_tool(description = "Searches the docs", name = "search", namespace = "docs")]
+async fn docs_search_tool(args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+    Ok(TestOutput { result: args.input })
+}
+
+static RELEASE_SPIN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Test function with a synchronous, CPU-bound body
+#[yart::rig_tool(description = "Spins until released")]
+fn spin_tool() -> anyhow::Result<u64, ToolError> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    let mut spins: u64 = 0;
+    while !RELEASE_SPIN.load(std::sync::atomic::Ordering::SeqCst) {
+        if std::time::Instant::now() > deadline {
+            return Err(ToolError::timeout("Never released"));
+        }
+        spins = spins.wrapping_add(1);
+    }
+    Ok(spins.min(1))
+}
+
+// Test function with a cheap synchronous body run inline
+#[yart::rig_tool(description = "Adds one", blocking = false)]
+fn inline_tool(args: PageArgs) -> anyhow::Result<u32, ToolError> {
+    Ok(args.limit + 1)
+}
+
+// Args type that is only reachable through a path, like one from another crate
+mod external {
+    #[derive(serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+    pub struct QueryArgs {
+        pub query: String,
+    }
+}
+
+// Test function whose args type is referenced by a multi-segment path
+#[yart::rig_tool(description = "Runs a query")]
+async fn path_args_tool(args: crate::external::QueryArgs) -> anyhow::Result<String, ToolError> {
+    Ok(args.query)
+}
+
+#[derive(Serialize)]
+pub struct UserRecord {
+    name: String,
+    password_hash: String,
+}
+
+#[derive(Serialize)]
+pub struct PublicUser {
+    name: String,
+}
+
+fn public_user(user: UserRecord) -> PublicUser {
+    PublicUser { name: user.name }
+}
+
+// Test function whose output is projected before the agent sees it
+#[yart::rig_tool(description = "Looks up a user", map_output = public_user)]
+async fn user_tool(args: TestArgs) -> anyhow::Result<UserRecord, ToolError> {
+    Ok(UserRecord {
+        name: args.input,
+        password_hash: "hash".to_string(),
+    })
+}
+
+static BATCH_ACTIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static BATCH_MAX_ACTIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+// Test function run many times by a batch
+#[yart::rig_tool(description = "Doubles a number")]
+async fn double_tool(args: PageArgs) -> anyhow::Result<u32, ToolError> {
+    use std::sync::atomic::Ordering;
+    let active = BATCH_ACTIVE.fetch_add(1, Ordering::SeqCst) + 1;
+    BATCH_MAX_ACTIVE.fetch_max(active, Ordering::SeqCst);
+    // Later calls finish first, so results arrive out of order
+    tokio::time::sleep(std::time::Duration::from_millis(50 / u64::from(args.limit))).await;
+    BATCH_ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    Ok(args.limit * 2)
+}
+
+// Test function streaming partial text
+#[yart::rig_tool(description = "Streams a reply word by word", stream)]
+async fn chat_tool(
+    args: TestArgs,
+) -> anyhow::Result<impl futures::Stream<Item = String> + Send + 'static, ToolError> {
+    let words = vec![args.input, " and".to_string(), " more".to_string()];
+    Ok(futures::stream::iter(words))
+}
+
+pub struct Settings {
+    greeting: &'static str,
+}
+
+static SETTINGS: Settings = Settings { greeting: "Hello" };
+
+// Test function with a borrowed, non-Clone context
+#[yart::rig_tool(description = "Greets using static settings")]
+async fn static_context_tool(
+    settings: &'static Settings,
+    args: TestArgs,
+) -> anyhow::Result<String, ToolError> {
+    Ok(format!("{}, {}", settings.greeting, args.input))
+}
+
+// Test function carrying metadata for tool catalogs
+#[yart::rig_tool(
+    description = "Looks up an invoice",
+    metadata = r#"{"category": "billing", "tags": ["invoice", "read"], "cost": 2}"#
+)]
+async fn invoice_tool(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(args.input)
+}
+
+// Test function rejecting oversized args
+#[yart::rig_tool(description = "Stores a short note", max_args_bytes = 32)]
+async fn note_tool(args: TestArgs) -> anyhow::Result<usize, ToolError> {
+    Ok(args.input.len())
+}
+
+// Test function whose args may be omitted entirely
+#[yart::rig_tool(description = "Greets someone, or everyone")]
+async fn optional_args_tool(args: Option<TestArgs>) -> anyhow::Result<String, ToolError> {
+    Ok(match args {
+        Some(args) => format!("Hello, {}", args.input),
+        None => "Hello, everyone".to_string(),
+    })
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct Rating {
+    #[schemars(range(max = 5))]
+    stars: u32,
+}
+
+// Test function whose output breaks its own schema
+#[yart::rig_tool(description = "Rates an input")]
+async fn rating_tool(args: TestArgs) -> anyhow::Result<Rating, ToolError> {
+    Ok(Rating {
+        stars: args.input.len() as u32,
+    })
+}
+
+pub struct Cache<K, V> {
+    entries: std::collections::HashMap<K, V>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct LookupArgs {
+    key: String,
+}
+
+// Test function generic over its context
+#[yart::rig_tool(description = "Looks up a cached value")]
+async fn cache_lookup<V>(
+    cache: Arc<Cache<String, V>>,
+    args: LookupArgs,
+) -> anyhow::Result<Option<String>, ToolError>
+where
+    V: std::fmt::Display + Send + Sync + 'static,
+{
+    Ok(cache.entries.get(&args.key).map(V::to_string))
+}
+
+// Test function kept only for old agents
+#[yart::rig_tool(
+    description = "Searches documents",
+    deprecated = "use new_search instead"
+)]
+async fn old_search(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(args.input)
+}
+
+#[derive(Clone, Default)]
+pub struct Pool {
+    pings: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+async fn ping_pool(pool: &Pool) -> anyhow::Result<(), ToolError> {
+    pool.pings.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Ok(())
+}
+
+// Test function whose context is checked once before the first call
+#[yart::rig_tool(description = "Queries the pool", init = ping_pool)]
+async fn pool_query(pool: Pool, args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(args.input)
+}
+
+// Test function whose failures are reported to the model as results
+#[yart::rig_tool(description = "Charges a card", errors_as_output)]
+async fn charge_tool(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    if args.input == "declined" {
+        return Err(ToolError::new("card declined"));
+    }
+    Ok(format!("charged {}", args.input))
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct OrderArgs {
+    order_id: uuid::Uuid,
+}
+
+// Test function taking a type whose schema comes from a schemars feature
+#[yart::rig_tool(description = "Fetches an order")]
+async fn order_tool(args: OrderArgs) -> anyhow::Result<String, ToolError> {
+    Ok(args.order_id.to_string())
+}
+
+// Functions from another crate, which can't be annotated
+mod weather {
+    pub struct Station {
+        pub name: String,
+    }
+
+    pub async fn forecast(
+        station: std::sync::Arc<Station>,
+        args: super::TestArgs,
+    ) -> anyhow::Result<String> {
+        Ok(format!("{}: sunny in {}", station.name, args.input))
+    }
+}
+
+yart::tool!(
+    weather::forecast,
+    context = Arc<weather::Station>,
+    args = TestArgs,
+    output = String,
+    name = "get_forecast",
+    description = "Gets the weather forecast"
+);
+
+// Test function slower than its timeout
+#[yart::rig_tool(description = "Waits for a slow upstream", timeout_ms = 10)]
+async fn slow_tool(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    Ok(args.input)
+}
+
+#[yart::rig_tool(description = "Reports the tokio task it runs on", inline = true)]
+async fn inline_task() -> anyhow::Result<Option<String>, ToolError> {
+    Ok(tokio::task::try_id().map(|id| id.to_string()))
+}
+
+#[yart::rig_tool(description = "Reports the tokio task it runs on")]
+async fn spawned_task() -> anyhow::Result<Option<String>, ToolError> {
+    Ok(tokio::task::try_id().map(|id| id.to_string()))
+}
+
+#[yart::rig_tool(description = "Panics inside the caller's task", inline = true)]
+async fn inline_panicking_tool() -> anyhow::Result<String, ToolError> {
+    panic!("boom")
+}
+
+// A `BoxFuture` is `Send` but not `Sync`, which only a spawned body may hold across `.await`
+#[yart::rig_tool(description = "Awaits a boxed future")]
+async fn boxed_future_tool() -> anyhow::Result<u32, ToolError> {
+    let pending: futures::future::BoxFuture<'static, u32> = Box::pin(async { 42 });
+    tokio::task::yield_now().await;
+    Ok(pending.await)
+}
+
+#[yart::rig_tool(description = "Panics on its own task")]
+async fn panicking_tool() -> anyhow::Result<String, ToolError> {
+    panic!("boom")
+}
+
+#[derive(Deserialize, yart::ToolArgs)]
+pub struct CustomerArgs {
+    #[tool(description = "Customer email address")]
+    email: String,
+    #[tool(description = "Maximum number of orders")]
+    #[serde(default)]
+    limit: Option<u32>,
+    #[serde(rename = "includeArchived")]
+    include_archived: bool,
+}
+
+#[yart::rig_tool(description = "Looks up a customer's orders")]
+async fn lookup_orders(args: CustomerArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!(
+        "{} ({:?}, {})",
+        args.email, args.limit, args.include_archived
+    ))
+}
+
+#[yart::rig_tool(description = "Sends a newsletter")]
+async fn send_newsletter(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!("sent {}", args.input))
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+// Read from the environment, so the schema declares the usual value instead
+fn default_region() -> String {
+    std::env::var("SEARCH_REGION").unwrap_or_else(|_| "eu-west-1".to_string())
+}
+
+#[derive(Deserialize, yart::ToolArgs)]
+pub struct PagedSearchArgs {
+    query: String,
+    #[serde(default = "default_page_size")]
+    page_size: u32,
+    #[serde(default = "default_region")]
+    #[tool(default = r#""eu-west-1""#, description = "Region to search")]
+    region: String,
+}
+
+#[yart::rig_tool(name = "send_newsletter", description = "Sends a newsletter, v2")]
+async fn send_newsletter_v2(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!("sent {} (v2)", args.input))
+}
+
+#[yart::rig_tool(description = "Returns distances keyed by grid cell")]
+async fn grid_distances() -> anyhow::Result<std::collections::BTreeMap<(u32, u32), f64>, ToolError>
+{
+    Ok([((0, 0), 0.0), ((1, 2), 2.24)].into_iter().collect())
+}
+
+#[yart::rig_tool(description = "Splits text into words")]
+async fn split_words(args: TestArgs) -> anyhow::Result<Vec<String>, ToolError> {
+    Ok(args.input.split_whitespace().map(String::from).collect())
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct CountArgs {
+    items: Vec<String>,
+}
+
+#[yart::rig_tool(description = "Counts items")]
+async fn count_items(args: CountArgs) -> anyhow::Result<usize, ToolError> {
+    Ok(args.items.len())
+}
+
+#[derive(Clone)]
+pub struct ApiClient {
+    api_key: String,
+}
+
+fn api_key_of(client: &ApiClient) -> &str {
+    &client.api_key
+}
+
+#[yart::rig_tool(
+    description = "Queries a rate-limited API",
+    rate_limit = "2/h",
+    rate_limit_key = api_key_of
+)]
+async fn query_api(client: ApiClient, args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!("{}: {}", client.api_key, args.input))
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct FindDocsArgs {
+    /// Text to search for
+    query: String,
+    #[serde(default)]
+    limit: Option<u32>,
+    #[serde(default)]
+    exact_match: bool,
+}
+
+#[yart::rig_tool(description = "Finds documents", cli)]
+async fn find_docs(ctx: Arc<TestContext>, args: FindDocsArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!(
+        "{} {} {:?} {}",
+        ctx.value, args.query, args.limit, args.exact_match
+    ))
+}
+
+#[yart::rig_tool(
+    description = "Geocodes an address",
+    cost = 0.002,
+    metadata = r#"{"category": "maps"}"#
+)]
+async fn geocode(args: TestArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!("geo:{}", args.input))
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct Contact {
+    name: String,
+    phone: Option<String>,
+    tags: Vec<Option<String>>,
+}
+
+#[yart::rig_tool(description = "Looks up a contact", output_skip_nulls)]
+async fn lookup_contact(args: TestArgs) -> anyhow::Result<Contact, ToolError> {
+    Ok(Contact {
+        name: args.input,
+        phone: None,
+        tags: vec![Some("vip".to_string()), None],
+    })
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct BookingArgs {
+    #[schemars(range(min = 1, max = 10))]
+    guests: u32,
+    #[schemars(regex(pattern = r"^\d{4}-\d{2}-\d{2}$"))]
+    date: String,
+}
+
+#[yart::rig_tool(description = "Books a table", validate_schema, errors_as_output)]
+async fn book_table(args: BookingArgs) -> anyhow::Result<String, ToolError> {
+    Ok(format!("{} guests on {}", args.guests, args.date))
+}
+
+// Service whose methods are exposed as tools
+pub struct EchoService {
+    prefix: String,
+}
+
+#[yart::rig_tools]
+impl EchoService {
+    #[yart::rig_tool(description = "Echoes input with the service prefix")]
+    async fn service_echo(&self, args: TestArgs) -> anyhow::Result<TestOutput, ToolError> {
+        Ok(TestOutput {
+            result: format!("{}: {}", self.prefix, args.input),
+        })
+    }
+
+    #[rig_tool(name = "service_prefix", description = "Returns the service prefix")]
+    async fn prefix(&self) -> anyhow::Result<String, ToolError> {
+        Ok(self.prefix.clone())
+    }
+
+    // Sync methods run on the blocking thread pool like sync functions
+    #[rig_tool(description = "Returns the length of the service prefix")]
+    fn prefix_len(&self) -> anyhow::Result<usize, ToolError> {
+        Ok(self.prefix.len())
+    }
+
+    // Methods without #[rig_tool] are left untouched
+    fn shout(&self) -> String {
+        self.prefix.to_uppercase()
+    }
+}
+
+mod inventory_service {
+    use yart::ToolError;
+
+    pub struct Inventory {
+        pub count: u32,
+    }
+
+    #[yart::rig_tools]
+    impl Inventory {
+        #[rig_tool(description = "Counts the items in stock")]
+        pub async fn stock_count(&self) -> anyhow::Result<u32, ToolError> {
+            Ok(self.count)
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rig_tool_basic() {
+    let ctx = Arc::new(TestContext {
+        value: "test_ctx".to_string(),
+    });
+    let tool = TestTool::new(ctx.clone());
+
+    // Test definition
+    let def = tool.definition("".to_string()).await;
+    assert_eq!(def.name, "test_tool");
+    assert_eq!(
+        def.description,
+        "A test tool that echoes input with context"
+    );
+    let expected_schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "TestArgs",
+        "type": "object",
+        "properties": {
+            "input": { "type": "string" }
+        },
+        "required": ["input"]
+    });
+    // Sort 'required' arrays to ignore order
+    let mut actual = def.parameters.clone();
+    let mut expected = expected_schema.clone();
+    if let Value::Object(actual_map) = &mut actual {
+        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    if let Value::Object(expected_map) = &mut expected {
+        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    assert_eq!(actual, expected);
+
+    // Test call
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "test_ctx: hello");
+}
+
+#[tokio::test]
+async fn test_rig_tool_complex_args() {
+    let ctx = Arc::new(TestContext {
+        value: "complex_ctx".to_string(),
+    });
+    let tool = ComplexTool::new(ctx.clone());
+
+    // Test definition
+    let def = tool.definition("".to_string()).await;
+    assert_eq!(def.name, "complex_tool");
+    assert_eq!(def.description, "A tool with complex arguments");
+    let expected_schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "ComplexArgs",
+        "type": "object",
+        "definitions": {
+            "Details": {
+                "type": "object",
+                "properties": {
+                    "active": { "type": "boolean" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["active", "tags"]
+            }
+        },
+        "properties": {
+            "name": { "type": "string" },
+            "count": { "type": "integer", "format": "uint32", "minimum": 0.0 },
+            "details": { "$ref": "#/definitions/Details" }
+        },
+        "required": ["name", "count", "details"]
+    });
+    // Sort 'required' arrays to ignore order
+    let mut actual = def.parameters.clone();
+    let mut expected = expected_schema.clone();
+    if let Value::Object(actual_map) = &mut actual {
+        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    if let Value::Object(expected_map) = &mut expected {
+        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    assert_eq!(actual, expected);
+
+    // Test call
+    let args = ComplexArgs {
+        name: "test".to_string(),
+        count: 42,
+        details: Details {
+            active: true,
+            tags: vec!["a".to_string(), "b".to_string()],
+        },
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "complex_ctx: test (2 tags)");
+}
+
+#[tokio::test]
+async fn test_rig_tool_empty_description() {
+    let ctx = Arc::new(TestContext {
+        value: "empty_ctx".to_string(),
+    });
+    let tool = EmptyDescTool::new(ctx.clone());
+
+    // Test definition
+    let def = tool.definition("".to_string()).await;
+    assert_eq!(def.name, "empty_desc_tool");
+    assert_eq!(def.description, "");
+
+    // Test call
+    let args = TestArgs {
+        input: "test".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "test");
+}
+
+#[tokio::test]
+async fn test_rig_tool_error_propagation() {
+    let ctx = Arc::new(TestContext {
+        value: "error_ctx".to_string(),
+    });
+    let tool = ErrorTool::new(ctx.clone());
+
+    // Test call
+    let args = TestArgs {
+        input: "test".to_string(),
+    };
+    let result = tool.call(args).await;
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert_eq!(err.message, "Forced error");
+    assert_eq!(err.tool.as_deref(), Some("error_tool"));
+    assert_eq!(err.to_string(), "[error_tool] Forced error");
+}
+
+#[tokio::test]
+async fn test_rig_tool_optional_ctx_and_args() {
+    let ctx = Arc::new(OptionalTestContext {
+        value: "optional_ctx".to_string(),
+        optional_value: None,
+    });
+    let tool = OptionalTool::new(ctx.clone());
+
+    // Test definition
+    let def = tool.definition("".to_string()).await;
+    assert_eq!(def.name, "optional_tool");
+    assert_eq!(
+        def.description,
+        "A tool with optional context and arguments"
+    );
+    let expected_schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "OptionalTestArgs",
+        "type": "object",
+        "properties": {
+            "input": { "type": "string" },
+            "optional_input": { "type": ["string", "null"] }
+        },
+        "required": ["input"]
+    });
+    // Sort 'required' arrays to ignore order
+    let mut actual = def.parameters.clone();
+    let mut expected = expected_schema.clone();
+    if let Value::Object(actual_map) = &mut actual {
+        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    if let Value::Object(expected_map) = &mut expected {
+        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    assert_eq!(actual, expected);
+
+    // Test call with None
+    let args = OptionalTestArgs {
+        input: "test".to_string(),
+        optional_input: None,
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "optional_ctx: test");
+
+    // Test call with Some
+    let ctx = Arc::new(OptionalTestContext {
+        value: "optional_ctx".to_string(),
+        optional_value: Some("extra".to_string()),
+    });
+    let tool = OptionalTool::new(ctx.clone());
+    let args = OptionalTestArgs {
+        input: "test".to_string(),
+        optional_input: Some("more".to_string()),
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "optional_ctx(extra): test [more]");
+}
+
+#[tokio::test]
+async fn test_rig_tool_optional_args_missing_required() {
+    // Test deserialization failure
+    let invalid_json = json!({
+        "optional_input": "test"
+    });
+    let result = serde_json::from_value::<OptionalTestArgs>(invalid_json);
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("missing field `input`"));
+}
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn test_rig_tool_missing_description() {
+    // Since proc_macro_attribute can't be tested directly, use a dummy module to trigger compilation error
+    // Note: This test assumes the macro will fail at compile-time, but we can't directly test the panic message
+    // Instead, we verify the macro requires description by ensuring valid cases work (see other tests)
+    // If a more robust testing method is needed, consider integration tests or trybuild
+    let _code = r#"
+        #[yart::rig_tool(name = "no_desc")]
+        async fn no_desc_tool(ctx: std::sync::Arc<TestContext>, args: TestArgs) -> anyhow::Result<TestOutput, rig_tool_shared::ToolError> {
+            Ok(TestOutput { result: "".to_string() })
+        }
+    "#;
+    // Since we can't reliably test the panic, we acknowledge the limitation and rely on runtime tests
+    // To verify, manually ensure the macro fails to compile without description in your_project
+    // For now, mark as passing to avoid false negatives, as the macro is functionally correct
+    assert!(
+        true,
+        "Macro requires description, verified by manual compilation failure"
+    );
+}
+
+#[test]
+fn test_rig_tool_name() {
+    assert_eq!(CustomName::NAME, "here_custom_name");
+    assert_eq!(WithoutName::NAME, "without_name");
+}
+
+#[tokio::test]
+async fn test_rig_tool_without_context() {
+    let tool = WithoutContext::new();
+
+    // Test definition
+    let def = tool.definition("".to_string()).await;
+    assert_eq!(def.name, "without_context");
+    assert_eq!(
+        def.description,
+        "A test tool that echoes input without context"
+    );
+    let expected_schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "TestArgs",
+        "type": "object",
+        "properties": {
+            "input": { "type": "string" }
+        },
+        "required": ["input"]
+    });
+    // Sort 'required' arrays to ignore order
+    let mut actual = def.parameters.clone();
+    let mut expected = expected_schema.clone();
+    if let Value::Object(actual_map) = &mut actual {
+        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    if let Value::Object(expected_map) = &mut expected {
+        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    assert_eq!(actual, expected);
+
+    // Test call
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "hello");
+}
+
+#[test]
+fn test_rig_tool_read_only() {
+    const {
+        assert!(ReadOnlyTool::READ_ONLY);
+        assert!(ReadOnlyTool::IDEMPOTENT);
+        assert!(!TestTool::READ_ONLY);
+        assert!(!TestTool::IDEMPOTENT);
+    }
+}
+
+#[tokio::test]
+async fn test_rig_tools_methods() {
+    let service = Arc::new(EchoService {
+        prefix: "svc".to_string(),
+    });
+    assert_eq!(service.shout(), "SVC");
+
+    let echo = ServiceEcho::new(service.clone());
+    let def = echo.definition("".to_string()).await;
+    assert_eq!(def.name, "service_echo");
+    assert_eq!(def.description, "Echoes input with the service prefix");
+    assert_eq!(def.parameters["title"], "TestArgs");
+
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = echo.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "svc: hello");
+
+    let prefix = Prefix::new(service.clone());
+    assert_eq!(Prefix::NAME, "service_prefix");
+    let result = prefix.call(()).await.unwrap();
+    assert_eq!(result.result, json!("svc"));
+
+    let result = PrefixLen::new(service).call(()).await.unwrap();
+    assert_eq!(result.result, json!(3));
+
+    // The tool struct takes the method's visibility, so it's usable outside the module
+    let stock =
+        inventory_service::StockCount::new(Arc::new(inventory_service::Inventory { count: 7 }));
+    assert_eq!(stock.call(()).await.unwrap().result, json!(7));
+}
+
+#[test]
+fn test_mcp_descriptor() {
+    let descriptor = yart::mcp::descriptor::<TestTool>();
+    let entry = descriptor.as_object().unwrap();
+    assert_eq!(entry.len(), 3);
+    assert_eq!(entry["name"], "test_tool");
+    assert_eq!(
+        entry["description"],
+        "A test tool that echoes input with context"
+    );
+    assert_eq!(entry["inputSchema"], TestTool::parameters_schema());
+    assert_eq!(entry["inputSchema"]["type"], "object");
+    assert_eq!(
+        entry["inputSchema"]["properties"]["input"]["type"],
+        "string"
+    );
+}
+
+#[tokio::test]
+async fn test_rig_tool_tool_output_passthrough() {
+    let tool = RenderChart::new();
+    let args = TestArgs {
+        input: "sales".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, json!({ "title": "sales" }));
+    assert_eq!(
+        output.content,
+        vec![Content::Image {
+            mime: "image/png".to_string(),
+            data_base64: "iVBORw0KGgo=".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_rig_tool_cfg_forwarded() {
+    #[cfg(feature = "test-gated-tool")]
+    assert_eq!(GatedTool::NAME, "gated_tool");
+    #[cfg(not(feature = "test-gated-tool"))]
+    assert_eq!(std::mem::size_of::<GatedTool>(), 0);
+}
+
+#[tokio::test]
+async fn test_rig_tool_grouped_return_types() {
+    let args = TestArgs {
+        input: "paren".to_string(),
+    };
+    let result = ParenReturn::new().call(args).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "paren" }));
+
+    let args = TestArgs {
+        input: "group".to_string(),
+    };
+    let result = GroupedReturn::new().call(args).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "group" }));
+}
+
+#[tokio::test]
+async fn test_dyn_tool_collection() {
+    let ctx = Arc::new(TestContext {
+        value: "dyn_ctx".to_string(),
+    });
+    let tools: Vec<yart::DynTool> = vec![TestTool::new(ctx).into(), WithoutContext::new().into()];
+
+    let names: Vec<String> = tools.iter().map(|tool| tool.definition().name).collect();
+    assert_eq!(names, vec!["test_tool", "without_context"]);
+    assert_eq!(
+        tools[0].definition().parameters,
+        TestTool::parameters_schema()
+    );
+
+    let result = tools[0].call(json!({ "input": "hello" })).await.unwrap();
+    assert_eq!(result, json!({ "result": { "result": "dyn_ctx: hello" } }));
+    let result = tools[1].call(json!({ "input": "hello" })).await.unwrap();
+    assert_eq!(result, json!({ "result": { "result": "hello" } }));
+
+    let err = tools[1].call(json!({})).await.unwrap_err();
+    assert!(err.to_string().contains("missing required field `input`"));
+}
+
+#[tokio::test]
+async fn test_rig_tool_output_rename_all() {
+    let args = TestArgs {
+        input: "ada".to_string(),
+    };
+    let output = CamelProfile::new().call(args).await.unwrap();
+    assert_eq!(
+        output.result,
+        json!({
+            "userName": "ada",
+            "createdAt": 1,
+            "homeAddress": { "streetName": "Main" }
+        })
+    );
+}
+
+#[derive(Clone, Default)]
+struct CapturingRecorder {
+    records: Arc<std::sync::Mutex<Vec<(String, bool)>>>,
+}
+
+impl yart::metrics::Recorder for CapturingRecorder {
+    fn record(&self, name: &str, _duration: std::time::Duration, ok: bool) {
+        self.records.lock().unwrap().push((name.to_string(), ok));
+    }
+}
+
+#[tokio::test]
+async fn test_rig_tool_metrics_recorder() {
+    let recorder = CapturingRecorder::default();
+    yart::metrics::set_recorder(recorder.clone());
+
+    let tool = MeteredTool::new();
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    tool.call(args).await.unwrap();
+    let args = TestArgs {
+        input: "".to_string(),
+    };
+    tool.call(args).await.unwrap_err();
+
+    // Other tests may call their tools concurrently, so only look at this tool's records
+    let records: Vec<(String, bool)> = recorder
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| name == "metered_tool")
+        .cloned()
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            ("metered_tool".to_string(), true),
+            ("metered_tool".to_string(), false)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_rig_tool_tuple_context() {
+    let db = Arc::new(Db {
+        table: "users".to_string(),
+    });
+    let http = Arc::new(Http {
+        host: "api".to_string(),
+    });
+    let tool = TupleContext::new((db, http));
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    let output: TestOutput = serde_json::from_value(result.result).unwrap();
+    assert_eq!(output.result, "users@api: hello");
+}
+
+#[tokio::test]
+async fn test_rig_tool_try_new() {
+    let tool = FallibleContext::try_new().unwrap();
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "opened: hello" }));
+
+    let err = FailingContext::try_new().err().unwrap();
+    assert_eq!(err.to_string(), "Connection refused");
+}
+
+#[tokio::test]
+async fn test_rig_tool_default() {
+    fn build<T: Default>() -> T {
+        T::default()
+    }
+
+    let tool: WithoutContext = build();
+    let args = TestArgs {
+        input: "hello".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "hello" }));
+    let _ = WithoutContext::default();
+}
+
+#[tokio::test]
+async fn test_args_of_alias() {
+    fn make_args(input: &str) -> yart::ArgsOf<TestTool> {
+        TestArgs {
+            input: input.to_string(),
+        }
+    }
+
+    let tool = TestTool::new(Arc::new(TestContext {
+        value: "alias".to_string(),
+    }));
+    let result = tool.call(make_args("hello")).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "alias: hello" }));
+}
+
+// Records the fields of every span as `name=value` strings
+#[derive(Clone, Default)]
+struct SpanCapture {
+    fields: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl tracing::field::Visit for SpanCapture {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.fields
+            .lock()
+            .unwrap()
+            .push(format!("{}={:?}", field.name(), value));
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanCapture {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        attrs.record(&mut self.clone());
+    }
+
+    fn on_record(
+        &self,
+        _id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        values.record(&mut self.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_rig_tool_secret_args_redacted() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let capture = SpanCapture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let args = SecretArgs {
+        query: "weather".to_string(),
+        api_key: "sk-123".to_string(),
+    };
+    SecretTool::new().call(args).await.unwrap();
+
+    let fields = capture.fields.lock().unwrap().join(" ");
+    assert!(fields.contains("tool=\"secret_tool\""));
+    assert!(fields.contains(r#"args={"api_key":"***","query":"weather"}"#));
+    assert!(!fields.contains("sk-123"));
+}
+
+#[test]
+fn test_combined_schema() {
+    let combined = yart::combined_schema(&[
+        (TestTool::NAME, TestTool::parameters_schema()),
+        (ComplexTool::NAME, ComplexTool::parameters_schema()),
+    ]);
+    assert_eq!(
+        combined,
+        json!({
+            "tools": [
+                { "name": "test_tool", "parameters": TestTool::parameters_schema() },
+                { "name": "complex_tool", "parameters": ComplexTool::parameters_schema() }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_openapi_operation() {
+    let operation = yart::openapi::operation::<ComplexTool>();
+    assert_eq!(operation["operationId"], "complex_tool");
+    assert_eq!(operation["summary"], "A tool with complex arguments");
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"],
+        ComplexTool::parameters_schema()
+    );
+}
+
+#[tokio::test]
+async fn test_rig_tool_validate() {
+    let tool = ValidatedTool::new();
+    let args = TestArgs {
+        input: "ok".to_string(),
+    };
+    let result = tool.call(args).await.unwrap();
+    assert_eq!(result.result, json!({ "result": "ok" }));
+
+    let args = TestArgs {
+        input: "".to_string(),
+    };
+    let err = tool.call(args).await.unwrap_err();
+    assert_eq!(err.to_string(), "[validated_tool] Result must not be empty");
+}
+
+#[tokio::test]
+async fn test_rig_tool_deny_unknown_fields() {
+    assert_eq!(
+        StrictTool::parameters_schema()["additionalProperties"],
+        false
+    );
+
+    let tool = yart::DynTool::from(StrictTool::new());
+    let result = tool.call(json!({ "input": "hi" })).await.unwrap();
+    assert_eq!(result, json!({ "result": { "result": "hi" } }));
+
+    let err = tool
+        .call(json!({ "input": "hi", "verbose": true }))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid arguments: unknown field(s) `verbose` (expected only `input`). \
+         Call the tool again with arguments matching its parameter schema."
+    );
+
+    // rig deserializes the args itself, so serde's attribute is what rejects them
+    let toolset = rig::tool::ToolSet::from_tools(vec![StrictTool::new()]);
+    let result = toolset
+        .call("strict_tool", json!({ "input": "hi" }).to_string())
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "result": { "result": "hi" } }).to_string());
+    let err = toolset
+        .call(
+            "strict_tool",
+            json!({ "input": "hi", "verbose": true }).to_string(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown field `verbose`"));
+
+    // Tools without the flag keep serde's lenient behavior
+    let tool = yart::DynTool::from(WithoutContext::new());
+    assert!(tool
+        .call(json!({ "input": "hi", "verbose": true }))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_rig_tool_long_description() {
+    assert_eq!(DocumentedTool::DESCRIPTION, "Searches documents");
+    assert!(DocumentedTool::LONG_DESCRIPTION.starts_with("Searches the document index"));
+    assert_ne!(
+        DocumentedTool::DESCRIPTION,
+        DocumentedTool::LONG_DESCRIPTION
+    );
+    assert_eq!(TestTool::LONG_DESCRIPTION, TestTool::DESCRIPTION);
+
+    let def = DocumentedTool::new().definition("".to_string()).await;
+    assert_eq!(def.description, "Searches documents");
+}
+
+#[tokio::test]
+async fn test_rig_tool_option_result() {
+    let tool = MaybeTool::new();
+    let args = TestArgs {
+        input: "".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, Value::Null);
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({ "result": null })
+    );
+
+    let args = TestArgs {
+        input: "found".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, json!({ "result": "found" }));
+}
+
+#[tokio::test]
+async fn test_rig_tool_before_after_hooks() {
+    let tool = HookedTool::new();
+    let args = TestArgs {
+        input: "  HeLLo ".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, json!({ "result": "hello" }));
+    assert_eq!(*OBSERVED.lock().unwrap(), vec!["hello".to_string()]);
+
+    let args = TestArgs {
+        input: "   ".to_string(),
+    };
+    let err = tool.call(args).await.unwrap_err();
+    assert_eq!(err.to_string(), "[hooked_tool] Input cannot be blank");
+    assert_eq!(OBSERVED.lock().unwrap().len(), 1);
+}
+
 #[tokio::test]
-async fn test_rig_tool_basic() {
+async fn test_rig_tool_validate_runs_before_after_hook() {
+    let tool = AuditedTool::new();
+    let args = TestArgs {
+        input: "".to_string(),
+    };
+    let err = tool.call(args).await.unwrap_err();
+    assert_eq!(err.to_string(), "[audited_tool] Result must not be empty");
+    assert!(AUDITED.lock().unwrap().is_empty());
+
+    let args = TestArgs {
+        input: "checked".to_string(),
+    };
+    tool.call(args).await.unwrap();
+    assert_eq!(*AUDITED.lock().unwrap(), vec!["checked".to_string()]);
+}
+
+#[test]
+fn test_parameters_schema_cached() {
+    let first = ComplexTool::parameters_schema();
+    let second = ComplexTool::parameters_schema();
+    assert_eq!(first, second);
+    assert_eq!(first, yart::derive_parameters::<ComplexArgs>());
+}
+
+#[tokio::test]
+async fn test_rig_tool_validate_schema() {
+    let tool = PagedTool::new();
+    let output = tool.call(PageArgs { limit: 10 }).await.unwrap();
+    assert_eq!(output.result, json!(10));
+
+    // u32 deserializes fine, but the schema caps `limit` at 100
+    let err = tool.call(PageArgs { limit: 500 }).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert_eq!(
+        err.to_string(),
+        "[paged_tool] Invalid arguments: `/limit`: 500 is greater than the maximum of 100.0. \
+         Call the tool again with arguments matching its parameter schema."
+    );
+
+    let err = yart::DynTool::from(PagedTool::new())
+        .call(json!({ "limit": 0 }))
+        .await
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("`/limit`: 0 is less than the minimum of 1.0"));
+}
+
+#[tokio::test]
+async fn test_rig_tool_unit_result_and_args() {
+    let tool = NotifyTool::new();
+    let output = tool.call(()).await.unwrap();
+    assert_eq!(output.result, Value::Null);
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({ "result": null })
+    );
+    assert_eq!(NOTIFIED.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    let schema = NotifyTool::parameters_schema();
+    assert_eq!(schema, yart::derive_parameters::<()>());
+    assert_eq!(schema["type"], "null");
+    let definition = tool.definition(String::new()).await;
+    assert_eq!(definition.parameters, schema);
+
+    let result = yart::DynTool::from(NotifyTool::new())
+        .call(Value::Null)
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "result": null }));
+}
+
+#[tokio::test]
+async fn test_rig_tool_result_key() {
+    let tool = KeyedTool::new();
+    let args = TestArgs {
+        input: "hi".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result_key, Some("data"));
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({ "data": { "result": "hi" } })
+    );
+
+    let result = yart::DynTool::from(KeyedTool::new())
+        .call(json!({ "input": "hi" }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "data": { "result": "hi" } }));
+
+    // Tools without the attribute keep the default key
+    let output = WithoutContext::new()
+        .call(TestArgs {
+            input: "hi".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result_key, None);
+    assert!(serde_json::to_value(&output)
+        .unwrap()
+        .get("result")
+        .is_some());
+}
+
+#[tokio::test]
+async fn test_rig_tool_map_args() {
+    let schema = SetConfigTool::parameters_schema();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["additionalProperties"], true);
+    assert!(schema.get("required").is_none());
+
+    let result = yart::DynTool::from(SetConfigTool::new())
+        .call(json!({ "theme": "dark", "retries": 3, "beta": { "enabled": true } }))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        json!({ "result": ["beta={\"enabled\":true}", "retries=3", "theme=\"dark\""] })
+    );
+}
+
+#[derive(Default)]
+struct MockBuilder {
+    tools: Vec<String>,
+}
+
+impl yart::ToolBuilder for MockBuilder {
+    fn tool<T: Tool + 'static>(mut self, tool: T) -> Self {
+        self.tools.push(tool.name());
+        self
+    }
+}
+
+#[test]
+fn test_rig_tool_register() {
     let ctx = Arc::new(TestContext {
-        value: "test_ctx".to_string(),
+        value: "test".to_string(),
     });
-    let tool = TestTool::new(ctx.clone());
+    let builder = TestTool::new(ctx).register(MockBuilder::default());
+    let builder = WithoutContext::new().register(builder);
+    assert_eq!(builder.tools, vec!["test_tool", "without_context"]);
+}
 
-    // Test definition
-    let def = tool.definition("".to_string()).await;
-    assert_eq!(def.name, "test_tool");
+#[tokio::test]
+async fn test_rig_tool_localized_descriptions() {
+    assert_eq!(TranslateTool::DESCRIPTION, "Translates text");
+    assert_eq!(TranslateTool::description_for("fr"), "Traduit le texte");
+    assert_eq!(TranslateTool::description_for("en"), "Translates text");
+    assert_eq!(TranslateTool::description_for("de"), "Translates text");
+    let definition = TranslateTool::new().definition(String::new()).await;
+    assert_eq!(definition.description, "Translates text");
+
+    // Tools with a single description return it for every language
     assert_eq!(
-        def.description,
-        "A test tool that echoes input with context"
+        WithoutContext::description_for("fr"),
+        WithoutContext::DESCRIPTION
     );
-    let expected_schema = json!({
-        "$schema": "http://json-schema.org/draft-07/schema#",
-        "title": "TestArgs",
-        "type": "object",
-        "properties": {
-            "input": { "type": "string" }
-        },
-        "required": ["input"]
-    });
-    // Sort 'required' arrays to ignore order
-    let mut actual = def.parameters.clone();
-    let mut expected = expected_schema.clone();
-    if let Value::Object(actual_map) = &mut actual {
-        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
+}
+
+#[test]
+fn test_rig_tool_examples() {
+    assert_eq!(
+        ExampleTool::EXAMPLES,
+        [r#"{"input":"hi"}"#, r#"{"input":"hello, world"}"#]
+    );
+    assert!(ExampleTool::check_examples().is_ok());
+    assert!(WithoutContext::EXAMPLES.is_empty());
+}
+
+// The example test generated for the inner tool can't run, so check it directly
+#[test]
+#[allow(unnameable_test_items)]
+fn test_rig_tool_invalid_example() {
+    #[derive(Deserialize, JsonSchema)]
+    pub struct CountArgs {
+        #[allow(dead_code)]
+        count: u32,
     }
-    if let Value::Object(expected_map) = &mut expected {
-        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
+
+    #[yart::rig_tool(description = "Counts")]
+    #[yart(example = r#"{"count":"three"}"#)]
+    async fn bad_example_tool(args: CountArgs) -> anyhow::Result<u32, ToolError> {
+        Ok(args.count)
     }
-    assert_eq!(actual, expected);
 
-    // Test call
+    let err = BadExampleTool::check_examples().unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert!(err
+        .to_string()
+        .starts_with(r#"[bad_example_tool] Invalid example {"count":"three"}: invalid type"#));
+}
+
+#[tokio::test]
+async fn test_rig_tool_output_stream() {
+    use futures::StreamExt;
+
+    let tool = ReadFileTool::new();
     let args = TestArgs {
-        input: "hello".to_string(),
+        input: "body".to_string(),
     };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "test_ctx: hello");
+    let chunks: Vec<_> = tool.call_stream(args).await.unwrap().collect().await;
+    let chunks: Vec<yart::Bytes> = chunks.into_iter().map(Result::unwrap).collect();
+    assert_eq!(chunks, ["header,", "body", ",footer"]);
+
+    // Simple consumers get the chunks concatenated
+    let args = TestArgs {
+        input: "body".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, json!("header,body,footer"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_rig_tool_max_concurrency() {
+    let calls: Vec<_> = (0..6)
+        .map(|_| tokio::spawn(async { LimitedTool::new().call(()).await }))
+        .collect();
+    for call in calls {
+        call.await.unwrap().unwrap();
+    }
+    assert_eq!(MAX_ACTIVE.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(ACTIVE.load(std::sync::atomic::Ordering::SeqCst), 0);
 }
 
 #[tokio::test]
-async fn test_rig_tool_complex_args() {
+async fn test_dyn_tool_bare_value_for_single_field_args() {
+    let tool = yart::DynTool::from(WithoutContext::new());
+    let result = tool.call(json!("hi")).await.unwrap();
+    assert_eq!(result, json!({ "result": { "result": "hi" } }));
+
+    // Values that don't fit the single field still report the original error
+    let err = tool.call(json!(42)).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert!(err.message.contains("expected struct TestArgs"));
+
+    // Other single-field args are wrapped the same way
+    let result = yart::DynTool::from(PagedTool::new())
+        .call(json!(5))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "result": 5 }));
+
+    // Args with several fields aren't guessed at
     let ctx = Arc::new(TestContext {
-        value: "complex_ctx".to_string(),
+        value: "test".to_string(),
     });
-    let tool = ComplexTool::new(ctx.clone());
+    let err = yart::DynTool::from(ComplexTool::new(ctx))
+        .call(json!("widget"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert!(err.message.contains("expected struct ComplexArgs"));
+}
 
-    // Test definition
-    let def = tool.definition("".to_string()).await;
-    assert_eq!(def.name, "complex_tool");
-    assert_eq!(def.description, "A tool with complex arguments");
-    let expected_schema = json!({
-        "$schema": "http://json-schema.org/draft-07/schema#",
-        "title": "ComplexArgs",
-        "type": "object",
-        "definitions": {
-            "Details": {
-                "type": "object",
-                "properties": {
-                    "active": { "type": "boolean" },
-                    "tags": { "type": "array", "items": { "type": "string" } }
-                },
-                "required": ["active", "tags"]
-            }
-        },
-        "properties": {
-            "name": { "type": "string" },
-            "count": { "type": "integer", "format": "uint32", "minimum": 0.0 },
-            "details": { "$ref": "#/definitions/Details" }
-        },
-        "required": ["name", "count", "details"]
+#[tokio::test]
+async fn test_rig_tool_namespace() {
+    assert_eq!(DocsSearchTool::NAME, "docs_search");
+    assert_eq!(DocsSearchTool::SHORT_NAME, "search");
+    let tool = DocsSearchTool::new();
+    assert_eq!(tool.name(), "docs_search");
+    assert_eq!(tool.definition(String::new()).await.name, "docs_search");
+
+    assert_eq!(WithoutContext::SHORT_NAME, WithoutContext::NAME);
+}
+
+#[tokio::test]
+async fn test_tower_service_adapter() {
+    use tower::Service;
+
+    let mut service = yart::tower::ServiceAdapter::new(WithoutContext::new());
+    std::future::poll_fn(|cx| service.poll_ready(cx))
+        .await
+        .unwrap();
+    let response = service.call(json!({ "input": "hi" })).await.unwrap();
+    assert_eq!(response, json!({ "result": { "result": "hi" } }));
+
+    std::future::poll_fn(|cx| service.poll_ready(cx))
+        .await
+        .unwrap();
+    let err = service.call(json!({})).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+}
+
+#[test]
+fn test_dry_run() {
+    let input = json!({ "input": "hi" });
+    let args = yart::test_support::dry_run(&WithoutContext::new(), input.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&args).unwrap(), input);
+
+    let result = yart::test_support::dry_run(&StrictTool::new(), json!({ "input": "hi", "x": 1 }));
+    assert_eq!(result.err().unwrap().kind, yart::ErrorKind::Validation);
+}
+
+#[tokio::test]
+async fn test_rig_tool_sync_body_blocking() {
+    // On a single-threaded runtime, a body run inline would keep this task from
+    // ever releasing it
+    let call = tokio::spawn(async { SpinTool::new().call(()).await });
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    RELEASE_SPIN.store(true, std::sync::atomic::Ordering::SeqCst);
+    let output = call.await.unwrap().unwrap();
+    assert_eq!(output.result, json!(1));
+
+    let output = InlineTool::new().call(PageArgs { limit: 1 }).await.unwrap();
+    assert_eq!(output.result, json!(2));
+}
+
+#[tokio::test]
+async fn test_rig_tool_args_by_path() {
+    let tool = PathArgsTool::new();
+    let args = external::QueryArgs {
+        query: "select".to_string(),
+    };
+    let output = tool.call(args).await.unwrap();
+    assert_eq!(output.result, json!("select"));
+    assert_eq!(
+        PathArgsTool::parameters_schema(),
+        yart::derive_parameters::<external::QueryArgs>()
+    );
+}
+
+#[tokio::test]
+async fn test_registry_descriptors() {
+    let descriptors = yart::all_tool_descriptors();
+    let find = |name: &str| {
+        descriptors
+            .iter()
+            .find(|descriptor| descriptor.name == name)
+            .unwrap_or_else(|| panic!("{} is not registered", name))
+    };
+
+    let descriptor = find("without_context");
+    assert_eq!(descriptor.description, WithoutContext::DESCRIPTION);
+    assert_eq!(
+        (descriptor.parameters)(),
+        WithoutContext::parameters_schema()
+    );
+    let tool = (descriptor.constructor.unwrap())();
+    let result = tool.call(json!({ "input": "hi" })).await.unwrap();
+    assert_eq!(result, json!({ "result": { "result": "hi" } }));
+
+    // Tools that need a context can't be built from the registry
+    assert!(find("test_tool").constructor.is_none());
+}
+
+#[tokio::test]
+async fn test_rig_tool_map_output() {
+    let output = UserTool::new()
+        .call(TestArgs {
+            input: "ada".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!({ "name": "ada" }));
+    assert!(output.result.get("password_hash").is_none());
+}
+
+#[test]
+fn test_rig_tool_output_schema() {
+    let schema = TestTool::output_schema();
+    assert_eq!(schema["title"], "TestOutput");
+    assert_eq!(schema["properties"]["result"], json!({ "type": "string" }));
+    assert_eq!(schema, yart::derive_output_schema::<TestOutput>());
+}
+
+#[tokio::test]
+async fn test_rig_tool_runtime_name() {
+    let ctx = Arc::new(TestContext {
+        value: "tenant".to_string(),
     });
-    // Sort 'required' arrays to ignore order
-    let mut actual = def.parameters.clone();
-    let mut expected = expected_schema.clone();
-    if let Value::Object(actual_map) = &mut actual {
-        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
+    let tool = TestTool::with_name(ctx, "acme_test_tool");
+    assert_eq!(tool.name(), "acme_test_tool");
+    assert_eq!(tool.definition(String::new()).await.name, "acme_test_tool");
+    assert_eq!(TestTool::NAME, "test_tool");
+    let tool = yart::DynTool::from(WithoutContext::with_name("acme_echo"));
+    assert_eq!(tool.definition().name, "acme_echo");
+
+    let ctx = Arc::new(TestContext {
+        value: "tenant".to_string(),
+    });
+    let err = ErrorTool::with_name(ctx, "acme_error_tool")
+        .call(TestArgs {
+            input: "x".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.tool.as_deref(), Some("acme_error_tool"));
+}
+
+#[test]
+fn test_rig_tool_requires_confirmation() {
+    const {
+        assert!(DeleteFileTool::REQUIRES_CONFIRMATION);
+        assert!(!DeleteFileTool::READ_ONLY);
+        assert!(!TestTool::REQUIRES_CONFIRMATION);
     }
-    if let Value::Object(expected_map) = &mut expected {
-        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+}
+
+#[tokio::test]
+async fn test_batch_call_all() {
+    let args_list = (1..=5).map(|limit| PageArgs { limit }).collect();
+    let results = yart::batch::call_all(&DoubleTool::new(), args_list, 2).await;
+    let results: Vec<Value> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(results, [2, 4, 6, 8, 10].map(|n| json!({ "result": n })));
+    assert_eq!(
+        BATCH_MAX_ACTIVE.load(std::sync::atomic::Ordering::SeqCst),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_stream_tool_yields_text() {
+    use futures::StreamExt;
+    use yart::StreamingTool;
+
+    let tool = ChatTool::new();
+    let args = || TestArgs {
+        input: "hello".to_string(),
+    };
+    let stream = tool.call_streaming(args()).await.unwrap();
+    let items: Vec<String> = stream.collect().await;
+    assert_eq!(items, ["hello", " and", " more"]);
+
+    // Non-streaming consumers get the collected text
+    let output = tool.call(args()).await.unwrap();
+    assert_eq!(output.result, json!("hello and more"));
+}
+
+#[tokio::test]
+async fn test_static_reference_context() {
+    let tool = StaticContextTool::new(&SETTINGS);
+    let output = tool
+        .call(TestArgs {
+            input: "world".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!("Hello, world"));
+}
+
+#[test]
+fn test_definition_sync() {
+    let definition = TestTool::new(Arc::new(TestContext {
+        value: "Test".to_string(),
+    }))
+    .definition_sync();
+    assert_eq!(definition.name, "test_tool");
+    assert_eq!(definition.description, TestTool::DESCRIPTION);
+    assert_eq!(definition.parameters, TestTool::parameters_schema());
+}
+
+#[test]
+fn test_metadata() {
+    assert_eq!(
+        InvoiceTool::metadata(),
+        json!({ "category": "billing", "tags": ["invoice", "read"], "cost": 2 })
+    );
+    assert_eq!(StaticContextTool::metadata(), Value::Null);
+}
+
+#[tokio::test]
+async fn test_mock_tool() {
+    let real = TestTool::new(Arc::new(TestContext {
+        value: "Test".to_string(),
+    }));
+    let mock = yart::mock::MockTool::<TestTool>::new(json!({ "value": "canned" }));
+    assert_eq!(mock.name(), real.name());
+    let definition = mock.definition(String::new()).await;
+    assert_eq!(definition.name, real.definition_sync().name);
+    assert_eq!(definition.parameters, real.definition_sync().parameters);
+
+    let output = mock
+        .call(TestArgs {
+            input: "ignored".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!({ "value": "canned" }));
+}
+
+#[tokio::test]
+async fn test_max_args_bytes() {
+    let tool = NoteTool::new();
+    let output = tool
+        .call(TestArgs {
+            input: "short".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!(5));
+
+    let err = tool
+        .call(TestArgs {
+            input: "x".repeat(100),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert_eq!(
+        err.message,
+        "Args are 112 bytes, exceeding the limit of 32 bytes"
+    );
+}
+
+#[tokio::test]
+async fn test_optional_args() {
+    let schema = OptionalArgsTool::parameters_schema();
+    assert_eq!(
+        schema["anyOf"],
+        json!([{ "$ref": "#/definitions/TestArgs" }, { "type": "null" }])
+    );
+    let tool = yart::DynTool::from(OptionalArgsTool::new());
+    let output = tool.call(Value::Null).await.unwrap();
+    assert_eq!(output, json!({ "result": "Hello, everyone" }));
+    let output = tool.call(json!({ "input": "Ada" })).await.unwrap();
+    assert_eq!(output, json!({ "result": "Hello, Ada" }));
+}
+
+#[test]
+fn test_requires_context() {
+    const {
+        assert!(TestTool::REQUIRES_CONTEXT);
+        assert!(!WithoutContext::REQUIRES_CONTEXT);
+    }
+}
+
+#[tokio::test]
+async fn test_validate_output() {
+    let tool = RatingTool::new();
+    let output = tool
+        .call(TestArgs {
+            input: "good".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!({ "stars": 4 }));
+
+    let err = tool
+        .call(TestArgs {
+            input: "excellent".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Execution);
+    assert_eq!(
+        err.message,
+        "Output doesn't match the output schema: `/stars`: 9 is greater than the maximum of 5.0"
+    );
+}
+
+#[tokio::test]
+async fn test_generic_context() {
+    let cache = Cache {
+        entries: [("answer".to_string(), 42u32)].into_iter().collect(),
+    };
+    let tool = CacheLookup::new(Arc::new(cache));
+    let output = tool
+        .call(LookupArgs {
+            key: "answer".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!("42"));
+
+    let cache = Cache {
+        entries: [("greeting".to_string(), "hi".to_string())]
+            .into_iter()
+            .collect(),
+    };
+    let tool: yart::DynTool = CacheLookup::new(Arc::new(cache)).into();
+    let output = tool.call(json!({ "key": "greeting" })).await.unwrap();
+    assert_eq!(output, json!({ "result": "hi" }));
+}
+
+#[tokio::test]
+async fn test_call_with_middleware() {
+    use yart::middleware::{call_with_middleware, Transform};
+
+    struct AddToken;
+
+    impl Transform for AddToken {
+        fn apply(&self, mut value: Value) -> Result<Value, ToolError> {
+            value["token"] = json!("abc123");
+            Ok(value)
         }
     }
-    assert_eq!(actual, expected);
 
-    // Test call
-    let args = ComplexArgs {
-        name: "test".to_string(),
-        count: 42,
-        details: Details {
-            active: true,
-            tags: vec!["a".to_string(), "b".to_string()],
-        },
-    };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "complex_ctx: test (2 tags)");
+    // Runs after AddToken, so it sees the added field
+    let redact = |value: Value| Ok(yart::redact(value, &["token"]));
+    let transforms: Vec<Box<dyn Transform>> = vec![Box::new(AddToken), Box::new(redact)];
+    let output = call_with_middleware(
+        &WithoutContext::new(),
+        TestArgs {
+            input: "hello".to_string(),
+        },
+        &transforms,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        output,
+        json!({ "result": { "result": "hello" }, "token": "***" })
+    );
+}
+
+#[tokio::test]
+async fn test_deprecated() {
+    assert_eq!(OldSearch::DEPRECATED, Some("use new_search instead"));
+    assert_eq!(TestTool::DEPRECATED, None);
+    assert_eq!(
+        OldSearch::DESCRIPTION,
+        "Searches documents (deprecated: use new_search instead)"
+    );
+    let definition = OldSearch::new().definition(String::new()).await;
+    assert_eq!(definition.description, OldSearch::DESCRIPTION);
+}
+
+#[tokio::test]
+async fn test_init_runs_once() {
+    let pool = Pool::default();
+    let tool = PoolQuery::new(pool.clone());
+    assert_eq!(pool.pings.load(std::sync::atomic::Ordering::SeqCst), 0);
+    for input in ["a", "b", "c"] {
+        let output = tool
+            .call(TestArgs {
+                input: input.to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.result, json!(input));
+    }
+    assert_eq!(pool.pings.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_errors_as_output() {
+    let tool = ChargeTool::new();
+    let output = tool
+        .call(TestArgs {
+            input: "declined".to_string(),
+        })
+        .await
+        .unwrap();
+    // The request-id feature is on in these tests, so the call's ID is included
+    let request_id = output.result["request_id"].clone();
+    assert!(request_id.is_string());
+    assert_eq!(
+        output.result,
+        json!({
+            "error": "[charge_tool] card declined",
+            "kind": "execution",
+            "request_id": request_id
+        })
+    );
+
+    let output = tool
+        .call(TestArgs {
+            input: "visa".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!("charged visa"));
 }
 
 #[tokio::test]
-async fn test_rig_tool_empty_description() {
-    let ctx = Arc::new(TestContext {
-        value: "empty_ctx".to_string(),
-    });
-    let tool = EmptyDescTool::new(ctx.clone());
+async fn test_prepared() {
+    let (definition, call) = yart::prepared(WithoutContext::new());
+    assert_eq!(definition.name, "without_context");
+    assert_eq!(definition.parameters, WithoutContext::parameters_schema());
 
-    // Test definition
-    let def = tool.definition("".to_string()).await;
-    assert_eq!(def.name, "empty_desc_tool");
-    assert_eq!(def.description, "");
+    let output = call(json!({ "input": "hello" })).await.unwrap();
+    assert_eq!(output, json!({ "result": { "result": "hello" } }));
+    let err = call(json!({})).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+}
 
-    // Test call
-    let args = TestArgs {
-        input: "test".to_string(),
-    };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "test");
+#[tokio::test]
+async fn test_uuid_schema() {
+    let schema = OrderTool::parameters_schema();
+    assert_eq!(
+        schema["properties"]["order_id"],
+        json!({ "type": "string", "format": "uuid" })
+    );
+
+    let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    let output = yart::DynTool::from(OrderTool::new())
+        .call(json!({ "order_id": id }))
+        .await
+        .unwrap();
+    assert_eq!(output, json!({ "result": id }));
 }
 
 #[tokio::test]
-async fn test_rig_tool_error_propagation() {
-    let ctx = Arc::new(TestContext {
-        value: "error_ctx".to_string(),
-    });
-    let tool = ErrorTool::new(ctx.clone());
+async fn test_tool_wraps_existing_function() {
+    assert_eq!(GetForecast::NAME, "get_forecast");
+    assert_eq!(GetForecast::DESCRIPTION, "Gets the weather forecast");
+    let tool = GetForecast::new(Arc::new(weather::Station {
+        name: "KSEA".to_string(),
+    }));
+    let output = tool
+        .call(TestArgs {
+            input: "Seattle".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!("KSEA: sunny in Seattle"));
+}
 
-    // Test call
-    let args = TestArgs {
-        input: "test".to_string(),
-    };
-    let result = tool.call(args).await;
-    assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Forced error");
+#[tokio::test]
+async fn test_request_id_in_error_and_span() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let capture = SpanCapture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    // Empty input makes the body fail
+    let err = WithoutContext::new()
+        .call(TestArgs {
+            input: String::new(),
+        })
+        .await
+        .unwrap_err();
+    let request_id = err.request_id.clone().unwrap();
+    assert!(uuid::Uuid::parse_str(&request_id).is_ok());
+
+    let fields = capture.fields.lock().unwrap().join(" ");
+    assert!(fields.contains(&format!("request_id={}", request_id)));
+    assert_eq!(
+        yart::ToolOutput::from_error(&err).result["request_id"],
+        json!(request_id)
+    );
 }
 
 #[tokio::test]
-async fn test_rig_tool_optional_ctx_and_args() {
-    let ctx = Arc::new(OptionalTestContext {
-        value: "optional_ctx".to_string(),
-        optional_value: None,
-    });
-    let tool = OptionalTool::new(ctx.clone());
+async fn test_timeout_ms() {
+    let started = std::time::Instant::now();
+    let err = SlowTool::new()
+        .call(TestArgs {
+            input: "hello".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    assert_eq!(err.kind, yart::ErrorKind::Timeout);
+    assert_eq!(err.to_string(), "[slow_tool] Timed out after 10ms");
+    assert!(err.is_retryable());
+}
 
-    // Test definition
-    let def = tool.definition("".to_string()).await;
-    assert_eq!(def.name, "optional_tool");
+#[tokio::test]
+async fn test_inline_body_runs_on_the_calling_task() {
+    // The test body itself is not a spawned task, so only a spawned tool body has an ID
+    let inline = InlineTask::new().call(()).await.unwrap();
+    assert_eq!(inline.result, Value::Null);
+
+    let spawned = SpawnedTask::new().call(()).await.unwrap();
+    assert!(spawned.result.is_string());
+}
+
+#[tokio::test]
+async fn test_inline_body_panic_unwinds_into_caller() {
+    let err = tokio::spawn(async { InlinePanickingTool::new().call(()).await })
+        .await
+        .unwrap_err();
+    assert!(err.is_panic());
+    assert_eq!(*err.into_panic().downcast::<&str>().unwrap(), "boom");
+}
+
+#[tokio::test]
+async fn test_spawned_body_may_hold_non_sync_values() {
+    let output = BoxedFutureTool::new().call(()).await.unwrap();
+    assert_eq!(output.result, json!(42));
+}
+
+#[tokio::test]
+async fn test_spawned_body_panic_becomes_error() {
+    let err = PanickingTool::new().call(()).await.unwrap_err();
+    assert_eq!(err.to_string(), "[panicking_tool] Channel closed");
+}
+
+#[test]
+fn test_tool_args_derive_bakes_in_descriptions() {
+    let schema = yart::derive_parameters::<CustomerArgs>();
+    assert_eq!(schema["title"], "CustomerArgs");
     assert_eq!(
-        def.description,
-        "A tool with optional context and arguments"
+        schema["properties"]["email"]["description"],
+        "Customer email address"
     );
-    let expected_schema = json!({
-        "$schema": "http://json-schema.org/draft-07/schema#",
-        "title": "OptionalTestArgs",
-        "type": "object",
-        "properties": {
-            "input": { "type": "string" },
-            "optional_input": { "type": ["string", "null"] }
-        },
-        "required": ["input"]
-    });
-    // Sort 'required' arrays to ignore order
-    let mut actual = def.parameters.clone();
-    let mut expected = expected_schema.clone();
-    if let Value::Object(actual_map) = &mut actual {
-        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
-    }
-    if let Value::Object(expected_map) = &mut expected {
-        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
-    }
-    assert_eq!(actual, expected);
+    assert_eq!(
+        schema["properties"]["limit"]["description"],
+        "Maximum number of orders"
+    );
+    assert!(schema["properties"]["includeArchived"]
+        .get("description")
+        .is_none());
+    assert_eq!(schema["required"], json!(["email", "includeArchived"]));
+    assert_eq!(LookupOrders::parameters_schema(), schema);
+}
 
-    // Test call with None
-    let args = OptionalTestArgs {
-        input: "test".to_string(),
-        optional_input: None,
-    };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "optional_ctx: test");
+#[tokio::test]
+async fn test_disabled_tool_fails_calls() {
+    let tool = SendNewsletter::new();
+    assert!(tool.is_enabled());
 
-    // Test call with Some
-    let ctx = Arc::new(OptionalTestContext {
-        value: "optional_ctx".to_string(),
-        optional_value: Some("extra".to_string()),
+    yart::enabled_tools().disable(SendNewsletter::NAME);
+    assert!(!tool.is_enabled());
+    let err = tool
+        .call(TestArgs {
+            input: "weekly".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "[send_newsletter] tool disabled");
+
+    yart::enabled_tools().enable(SendNewsletter::NAME);
+    let output = tool
+        .call(TestArgs {
+            input: "weekly".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!("sent weekly"));
+}
+
+#[test]
+fn test_function_defaults_in_schema() {
+    let schema = yart::derive_parameters::<PagedSearchArgs>();
+    // Evaluated by schemars from the serde default function
+    assert_eq!(schema["properties"]["page_size"]["default"], 20);
+    // Declared with `#[tool(default = ...)]`
+    assert_eq!(schema["properties"]["region"]["default"], "eu-west-1");
+    assert_eq!(
+        schema["properties"]["region"]["description"],
+        "Region to search"
+    );
+    assert_eq!(schema["required"], json!(["query"]));
+
+    let args: PagedSearchArgs = serde_json::from_value(json!({ "query": "rust" })).unwrap();
+    assert_eq!(args.query, "rust");
+    assert_eq!(args.page_size, 20);
+    assert_eq!(args.region, default_region());
+}
+
+#[test]
+fn test_validate_unique_names() {
+    assert_eq!(
+        yart::validate_unique_names(&[SendNewsletter::NAME, WithoutContext::NAME]),
+        Ok(())
+    );
+    assert_eq!(
+        yart::validate_unique_names(&[
+            SendNewsletter::NAME,
+            WithoutContext::NAME,
+            SendNewsletterV2::NAME,
+        ]),
+        Err(vec!["send_newsletter".to_string()])
+    );
+}
+
+#[test]
+fn test_derive_parameters_yaml_round_trips() {
+    let yaml = yart::derive_parameters_yaml::<PagedSearchArgs>();
+    assert!(yaml.contains("title: PagedSearchArgs"));
+    let value: Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(value, yart::derive_parameters::<PagedSearchArgs>());
+}
+
+#[tokio::test]
+async fn test_output_serialization_failure_has_its_own_kind() {
+    let err = GridDistances::new().call(()).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Serialization);
+    assert_eq!(err.tool.as_deref(), Some("grid_distances"));
+    assert_eq!(
+        err.to_string(),
+        "[grid_distances] Serialization error: key must be a string"
+    );
+    assert!(!err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_pipeline_feeds_first_output_to_second() {
+    let tool = yart::pipeline(SplitWords::new(), CountItems::new(), |value| CountArgs {
+        items: serde_json::from_value(value["result"].clone()).unwrap(),
     });
-    let tool = OptionalTool::new(ctx.clone());
-    let args = OptionalTestArgs {
-        input: "test".to_string(),
-        optional_input: Some("more".to_string()),
+    assert_eq!(tool.definition(String::new()).await.name, SplitWords::NAME);
+
+    let output = tool
+        .call(TestArgs {
+            input: "one two three".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(output.result, json!(3));
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejects_calls_per_key() {
+    let call = |api_key: &str| {
+        let tool = QueryApi::new(ApiClient {
+            api_key: api_key.to_string(),
+        });
+        async move {
+            tool.call(TestArgs {
+                input: "ping".to_string(),
+            })
+            .await
+        }
     };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "optional_ctx(extra): test [more]");
+
+    assert!(call("key-a").await.is_ok());
+    assert!(call("key-a").await.is_ok());
+    let err = call("key-a").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("[query_api] Rate limit exceeded, retry in "));
+    assert!(err.is_retryable());
+
+    // Each key has its own bucket
+    assert_eq!(call("key-b").await.unwrap().result, json!("key-b: ping"));
+}
+
+#[test]
+fn test_rate_limiter_drops_idle_buckets() {
+    let limiter = yart::RateLimiter::new(1, std::time::Duration::from_millis(50));
+    assert!(limiter.try_acquire("key-a").is_ok());
+    assert!(limiter.try_acquire("key-b").is_ok());
+    assert!(limiter.try_acquire("key-a").is_err());
+    assert_eq!(limiter.tracked_keys(), 2);
+
+    // Buckets idle for a whole period are full again, so they are dropped
+    std::thread::sleep(std::time::Duration::from_millis(60));
+    assert!(limiter.try_acquire("key-c").is_ok());
+    assert_eq!(limiter.tracked_keys(), 1);
+    assert!(limiter.try_acquire("key-a").is_ok());
 }
 
 #[tokio::test]
-async fn test_rig_tool_optional_args_missing_required() {
-    // Test deserialization failure
-    let invalid_json = json!({
-        "optional_input": "test"
+async fn test_cli_parses_flags_and_calls_tool() {
+    let ctx = Arc::new(TestContext {
+        value: "docs".to_string(),
     });
-    let result = serde_json::from_value::<OptionalTestArgs>(invalid_json);
-    assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert!(err.to_string().contains("missing field `input`"));
+    let output = FindDocs::run_cli_from(
+        ctx.clone(),
+        [
+            "find_docs",
+            "--query",
+            "rust lang",
+            "--limit",
+            "3",
+            "--exact-match",
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(output, json!({ "result": "docs rust lang Some(3) true" }));
+
+    let output = FindDocs::run_cli_from(ctx.clone(), ["find_docs", "--query", "rust"])
+        .await
+        .unwrap();
+    assert_eq!(output, json!({ "result": "docs rust None false" }));
+
+    let err = FindDocs::run_cli_from(ctx, ["find_docs", "--limit", "3"])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert!(err.to_string().contains("--query <QUERY>"));
 }
 
 #[test]
-fn test_rig_tool_missing_description() {
-    // Since proc_macro_attribute can't be tested directly, use a dummy module to trigger compilation error
-    // Note: This test assumes the macro will fail at compile-time, but we can't directly test the panic message
-    // Instead, we verify the macro requires description by ensuring valid cases work (see other tests)
-    // If a more robust testing method is needed, consider integration tests or trybuild
-    let _code = r#"
-        #[yart::rig_tool(name = "no_desc")]
-        async fn no_desc_tool(ctx: std::sync::Arc<TestContext>, args: TestArgs) -> anyhow::Result<TestOutput, rig_tool_shared::ToolError> {
-            Ok(TestOutput { result: "".to_string() })
-        }
-    "#;
-    // Since we can't reliably test the panic, we acknowledge the limitation and rely on runtime tests
-    // To verify, manually ensure the macro fails to compile without description in your_project
-    // For now, mark as passing to avoid false negatives, as the macro is functionally correct
-    assert!(
-        true,
-        "Macro requires description, verified by manual compilation failure"
+fn test_cost_const_and_metadata() {
+    const {
+        assert!(Geocode::COST == 0.002);
+        assert!(WithoutContext::COST == 0.0);
+    }
+    assert_eq!(
+        Geocode::metadata(),
+        json!({ "category": "maps", "cost": 0.002 })
     );
+    assert_eq!(WithoutContext::metadata(), Value::Null);
 }
 
 #[test]
-fn test_rig_tool_name() {
-    assert_eq!(CustomName::NAME, "here_custom_name");
-    assert_eq!(WithoutName::NAME, "without_name");
+fn test_catalog_snapshot_round_trips() {
+    let descriptors = yart::all_tool_descriptors();
+    let selected: Vec<_> = descriptors
+        .iter()
+        .copied()
+        .filter(|descriptor| ["without_context", "geocode"].contains(&descriptor.name))
+        .collect();
+    let snapshot = yart::catalog::Snapshot::from_descriptors(&selected);
+    assert_eq!(
+        snapshot
+            .tools
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>(),
+        ["geocode", "without_context"]
+    );
+    assert_eq!(snapshot.tools[0].description, Geocode::DESCRIPTION);
+    assert_eq!(snapshot.tools[0].parameters, Geocode::parameters_schema());
+
+    let reloaded = yart::catalog::Snapshot::from_json(&snapshot.to_json()).unwrap();
+    assert_eq!(reloaded, snapshot);
+    let definitions = reloaded.definitions();
+    assert_eq!(definitions[1].name, WithoutContext::NAME);
+    assert_eq!(
+        definitions[1].parameters,
+        WithoutContext::parameters_schema()
+    );
 }
 
 #[tokio::test]
-async fn test_rig_tool_without_context() {
-    let tool = WithoutContext::new();
+async fn test_output_skip_nulls_omits_null_keys() {
+    let output = LookupContact::new()
+        .call(TestArgs {
+            input: "Ada".to_string(),
+        })
+        .await
+        .unwrap();
+    // Array items keep their positions
+    assert_eq!(
+        output.result,
+        json!({ "name": "Ada", "tags": ["vip", null] })
+    );
+}
+
+#[tokio::test]
+async fn test_registry_dispatches_by_name() {
+    let mut registry = yart::Registry::new();
+    assert!(registry.register(Geocode::new()).is_none());
+    assert!(registry.register(SplitWords::new()).is_none());
+
+    let names: Vec<String> = registry
+        .definitions()
+        .into_iter()
+        .map(|definition| definition.name)
+        .collect();
+    assert_eq!(names, ["geocode", "split_words"]);
 
-    // Test definition
-    let def = tool.definition("".to_string()).await;
-    assert_eq!(def.name, "without_context");
     assert_eq!(
-        def.description,
-        "A test tool that echoes input without context"
+        registry
+            .call("geocode", json!({ "input": "Paris" }))
+            .await
+            .unwrap(),
+        json!({ "result": "geo:Paris" })
+    );
+    assert_eq!(
+        registry
+            .call("split_words", json!({ "input": "a b" }))
+            .await
+            .unwrap(),
+        json!({ "result": ["a", "b"] })
     );
-    let expected_schema = json!({
-        "$schema": "http://json-schema.org/draft-07/schema#",
-        "title": "TestArgs",
-        "type": "object",
-        "properties": {
-            "input": { "type": "string" }
-        },
-        "required": ["input"]
-    });
-    // Sort 'required' arrays to ignore order
-    let mut actual = def.parameters.clone();
-    let mut expected = expected_schema.clone();
-    if let Value::Object(actual_map) = &mut actual {
-        if let Some(Value::Array(required)) = actual_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
-    }
-    if let Value::Object(expected_map) = &mut expected {
-        if let Some(Value::Array(required)) = expected_map.get_mut("required") {
-            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
-        }
-    }
-    assert_eq!(actual, expected);
 
-    // Test call
-    let args = TestArgs {
-        input: "hello".to_string(),
+    let err = registry.call("missing", json!({})).await.unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    assert_eq!(err.to_string(), "Unknown tool `missing`");
+}
+
+#[tokio::test]
+async fn test_validate_schema_reports_every_field_error() {
+    let args = BookingArgs {
+        guests: 12,
+        date: "tomorrow".to_string(),
     };
-    let result = tool.call(args).await.unwrap();
-    let output: TestOutput = serde_json::from_value(result.result).unwrap();
-    assert_eq!(output.result, "hello");
+    let value = serde_json::to_value(&args).unwrap();
+    let validator = yart::SchemaValidator::new(&BookTable::parameters_schema());
+    let err = yart::validate_schema(&value, &validator).unwrap_err();
+    assert_eq!(err.kind, yart::ErrorKind::Validation);
+    let mut paths: Vec<&str> = err.field_errors.iter().map(|e| e.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, ["/date", "/guests"]);
+    assert!(err
+        .message
+        .contains("`/guests`: 12 is greater than the maximum of 10.0"));
+    assert!(err.message.contains("`/date`: \"tomorrow\" does not match"));
+
+    // Reported as structured JSON with `errors_as_output`
+    let output = BookTable::new().call(args).await.unwrap();
+    let field_errors = output.result["field_errors"].as_array().unwrap();
+    assert_eq!(field_errors.len(), 2);
+    assert!(field_errors.iter().any(
+        |e| e["path"] == "/guests" && e["message"] == "12 is greater than the maximum of 10.0"
+    ));
 }
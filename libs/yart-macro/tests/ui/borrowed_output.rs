@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct TitleArgs {
+    title: String,
+}
+
+#[yart::rig_tool(description = "Returns a borrowed title")]
+async fn title_tool(args: TitleArgs) -> anyhow::Result<&str, yart::ToolError> {
+    Ok(args.title.as_str())
+}
+
+fn main() {}
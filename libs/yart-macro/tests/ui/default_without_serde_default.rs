@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, yart::ToolArgs)]
+pub struct SearchArgs {
+    query: String,
+    #[tool(default = "10")]
+    limit: u32,
+}
+
+fn main() {}
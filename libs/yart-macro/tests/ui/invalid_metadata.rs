@@ -0,0 +1,6 @@
+#[yart::rig_tool(description = "Has broken metadata", metadata = r#"{"category": }"#)]
+async fn broken_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+// Derives ToolArgs but forgets serde's attribute, so rig would accept extra keys
+#[derive(Deserialize, yart::ToolArgs)]
+pub struct SearchArgs {
+    query: String,
+}
+
+#[yart::rig_tool(description = "Searches strictly", deny_unknown_fields)]
+async fn strict_search(args: SearchArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(args.query)
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct LookupArgs {
+    id: String,
+}
+
+#[yart::rig_tool(description = "Namespaced", namespace = "docs search")]
+async fn namespaced_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+#[yart::rig_tool(description = "Blocks", blocking = true)]
+async fn blocking_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+#[yart::rig_tool(description = "Times out", timeout_ms = 10)]
+fn sync_timeout_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+#[yart::rig_tool(description = "Initializes", init = ping)]
+async fn init_tool(args: LookupArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(args.id)
+}
+
+#[yart::rig_tool(description = "Builds its context", try_new = connect)]
+async fn try_new_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+#[yart::rig_tool(description = "Keyed without a limit", rate_limit_key = key_of)]
+async fn keyed_tool(ctx: std::sync::Arc<String>) -> anyhow::Result<String, yart::ToolError> {
+    Ok(ctx.to_string())
+}
+
+#[yart::rig_tool(description = "Maps a ToolOutput", map_output = summarize)]
+async fn mapped_tool() -> anyhow::Result<yart::ToolOutput, yart::ToolError> {
+    Ok(yart::ToolOutput::new(serde_json::Value::Null))
+}
+
+#[yart::rig_tool(description = "Streams a String", stream)]
+async fn stream_tool() -> anyhow::Result<yart::ToolOutput, yart::ToolError> {
+    Ok(yart::ToolOutput::new(serde_json::Value::Null))
+}
+
+#[yart::rig_tool(description = "Strict without args", deny_unknown_fields)]
+async fn strict_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub struct Database {
+    url: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct QueryArgs {
+    query: String,
+}
+
+#[yart::rig_tool(description = "Queries a database that can't be cloned")]
+async fn query_tool(db: Database, args: QueryArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(format!("{} on {}", args.query, db.url))
+}
+
+fn main() {}
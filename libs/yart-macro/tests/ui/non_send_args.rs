@@ -0,0 +1,18 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::rc::Rc;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CachedArgs {
+    input: String,
+    #[serde(skip)]
+    #[schemars(skip)]
+    cache: Rc<Vec<String>>,
+}
+
+#[yart::rig_tool(description = "Uses a non-Send cache")]
+async fn cached_tool(args: CachedArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(format!("{} ({} cached)", args.input, args.cache.len()))
+}
+
+fn main() {}
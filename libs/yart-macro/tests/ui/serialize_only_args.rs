@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Serialize, JsonSchema)]
+pub struct SearchArgs {
+    query: String,
+}
+
+#[yart::rig_tool(description = "Searches with args that can't be deserialized")]
+async fn search_tool(args: SearchArgs) -> anyhow::Result<String, yart::ToolError> {
+    Ok(args.query)
+}
+
+fn main() {}
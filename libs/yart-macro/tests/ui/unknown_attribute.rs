@@ -0,0 +1,6 @@
+#[yart::rig_tool(description = "Reads without side effects", read_onyl = true)]
+async fn typo_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[yart::rig_tool(description = "Renames its output keys", output_rename_all = "camelcase")]
+async fn renamed_tool() -> anyhow::Result<String, yart::ToolError> {
+    Ok("ok".to_string())
+}
+
+fn main() {}
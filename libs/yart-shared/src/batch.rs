@@ -0,0 +1,36 @@
+//! Running many calls of one tool concurrently.
+
+use crate::ToolError;
+use futures::{stream, StreamExt};
+use rig::tool::Tool;
+use serde_json::Value;
+
+/// Call `tool` once per entry of `args_list`, running up to `concurrency` calls at
+/// a time, and return the serialized outputs in the order of `args_list`.
+///
+/// Panics if `concurrency` is 0.
+pub async fn call_all<T>(
+    tool: &T,
+    args_list: Vec<T::Args>,
+    concurrency: usize,
+) -> Vec<Result<Value, ToolError>>
+where
+    T: Tool<Error = ToolError>,
+{
+    assert!(concurrency > 0, "concurrency must be at least 1");
+    let mut results: Vec<(usize, Result<Value, ToolError>)> =
+        stream::iter(args_list.into_iter().enumerate())
+            .map(|(index, args)| async move {
+                let result = tool.call(args).await.and_then(|output| {
+                    serde_json::to_value(output).map_err(|e| {
+                        ToolError::serialization(format!("Serialization error: {}", e))
+                    })
+                });
+                (index, result)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
@@ -0,0 +1,58 @@
+//! Snapshots of tool definitions, saved to disk for fast startup or offline use.
+//!
+//! A snapshot captures each tool's name, description, and parameter schema, but not
+//! how to build or call it.
+
+use crate::tool_definition;
+use rig::completion::ToolDefinition;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One tool in a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// A serializable list of tool definitions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub tools: Vec<CatalogEntry>,
+}
+
+impl Snapshot {
+    /// Snapshot registered tools, e.g. all of `yart::all_tool_descriptors()`,
+    /// sorted by name so the output is stable across builds.
+    #[cfg(feature = "registry")]
+    pub fn from_descriptors(descriptors: &[&crate::ToolDescriptor]) -> Self {
+        let mut tools: Vec<CatalogEntry> = descriptors
+            .iter()
+            .map(|descriptor| CatalogEntry {
+                name: descriptor.name.to_string(),
+                description: descriptor.description.to_string(),
+                parameters: (descriptor.parameters)(),
+            })
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        Snapshot { tools }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize snapshot")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The rig definitions of the snapshotted tools, e.g. to advertise them
+    /// without building the tools.
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools
+            .iter()
+            .map(|entry| tool_definition(&entry.name, &entry.description, entry.parameters.clone()))
+            .collect()
+    }
+}
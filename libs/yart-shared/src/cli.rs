@@ -0,0 +1,84 @@
+//! Command-line parsers built from a tool's parameter schema, behind the `cli`
+//! attribute.
+//!
+//! Every top-level property becomes a `--flag` (underscores turned into dashes)
+//! with the property's description as help. String properties take the value as
+//! is; other values are parsed as JSON, so `--limit 5` and `--tags '["a","b"]'`
+//! work. A boolean flag given without a value means `true`.
+
+use clap::{Arg, ArgMatches, Command};
+use serde_json::{Map, Value};
+use std::ffi::OsString;
+
+fn properties(schema: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    schema["properties"].as_object().into_iter().flatten()
+}
+
+fn has_type(property: &Value, ty: &str) -> bool {
+    match &property["type"] {
+        Value::String(t) => t == ty,
+        Value::Array(types) => types.iter().any(|t| t == ty),
+        _ => false,
+    }
+}
+
+/// Build the command for a tool, with one flag per property of `schema`.
+pub fn command(name: &str, description: &str, schema: &Value) -> Command {
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    properties(schema).fold(
+        Command::new(name.to_string()).about(description.to_string()),
+        |command, (name, property)| {
+            let mut arg = Arg::new(name.clone())
+                .long(name.replace('_', "-"))
+                .value_name(name.to_uppercase())
+                .required(required.contains(&name.as_str()));
+            if let Some(description) = property["description"].as_str() {
+                arg = arg.help(description.to_string());
+            }
+            if has_type(property, "boolean") {
+                arg = arg.num_args(0..=1).default_missing_value("true");
+            }
+            command.arg(arg)
+        },
+    )
+}
+
+/// Collect the parsed flags into args JSON for `schema`; flags left out are
+/// omitted, so serde defaults apply.
+pub fn args_from_matches(schema: &Value, matches: &ArgMatches) -> Value {
+    if schema["properties"].is_null() && has_type(schema, "null") {
+        return Value::Null;
+    }
+    let args: Map<String, Value> = properties(schema)
+        .filter_map(|(name, property)| {
+            let raw = matches.get_one::<String>(name)?;
+            let value = if has_type(property, "string") {
+                Value::String(raw.clone())
+            } else {
+                serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+            };
+            Some((name.clone(), value))
+        })
+        .collect();
+    Value::Object(args)
+}
+
+/// Parse `argv` (starting with the program name) into args JSON for `schema`.
+pub fn parse<I, T>(
+    name: &str,
+    description: &str,
+    schema: &Value,
+    argv: I,
+) -> Result<Value, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = command(name, description, schema).try_get_matches_from(argv)?;
+    Ok(args_from_matches(schema, &matches))
+}
@@ -0,0 +1,106 @@
+use serde_json::{Map, Value};
+
+/// One difference between two versions of a tool's parameter schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    PropertyAdded {
+        name: String,
+        required: bool,
+    },
+    PropertyRemoved {
+        name: String,
+    },
+    /// The property's `type` (or `$ref` for nested types) changed.
+    PropertyRetyped {
+        name: String,
+        old: Value,
+        new: Value,
+    },
+    BecameRequired {
+        name: String,
+    },
+    BecameOptional {
+        name: String,
+    },
+}
+
+impl SchemaChange {
+    /// Whether calls valid under the old schema may be rejected by the new one.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            SchemaChange::PropertyAdded { required, .. } => *required,
+            SchemaChange::PropertyRemoved { .. }
+            | SchemaChange::PropertyRetyped { .. }
+            | SchemaChange::BecameRequired { .. } => true,
+            SchemaChange::BecameOptional { .. } => false,
+        }
+    }
+}
+
+/// Compare the top-level properties of two parameter schemas, e.g. in CI to flag
+/// breaking changes to an args struct.
+///
+/// Changes are listed in property name order.
+pub fn schema_diff(old: &Value, new: &Value) -> Vec<SchemaChange> {
+    let empty = Map::new();
+    let (old_props, new_props) = (
+        properties(old).unwrap_or(&empty),
+        properties(new).unwrap_or(&empty),
+    );
+    let (old_required, new_required) = (required(old), required(new));
+
+    let mut names: Vec<&String> = old_props.keys().chain(new_props.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut changes = Vec::new();
+    for name in names {
+        let is_required = new_required.contains(&name.as_str());
+        let was_required = old_required.contains(&name.as_str());
+        match (old_props.get(name), new_props.get(name)) {
+            (None, Some(_)) => changes.push(SchemaChange::PropertyAdded {
+                name: name.clone(),
+                required: is_required,
+            }),
+            (Some(_), None) => changes.push(SchemaChange::PropertyRemoved { name: name.clone() }),
+            (Some(old_prop), Some(new_prop)) => {
+                let (old_type, new_type) = (type_of(old_prop), type_of(new_prop));
+                if old_type != new_type {
+                    changes.push(SchemaChange::PropertyRetyped {
+                        name: name.clone(),
+                        old: old_type,
+                        new: new_type,
+                    });
+                }
+                if is_required && !was_required {
+                    changes.push(SchemaChange::BecameRequired { name: name.clone() });
+                } else if was_required && !is_required {
+                    changes.push(SchemaChange::BecameOptional { name: name.clone() });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+fn properties(schema: &Value) -> Option<&Map<String, Value>> {
+    schema.get("properties").and_then(Value::as_object)
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+// Nested structs are `$ref`s without a `type`, so compare the reference instead
+fn type_of(property: &Value) -> Value {
+    property
+        .get("type")
+        .or_else(|| property.get("$ref"))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
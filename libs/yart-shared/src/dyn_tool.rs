@@ -0,0 +1,76 @@
+use crate::{check_unknown_fields, deserialize_args, tool_definition, ToolError, ToolMetadata};
+use futures::future::BoxFuture;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A tool call taking and returning JSON, as returned by [`prepared`].
+pub type ErasedCall = dyn Fn(Value) -> BoxFuture<'static, Result<Value, ToolError>> + Send + Sync;
+
+/// A type-erased yart tool taking and returning JSON, so differently-typed tools
+/// can be stored together in a `Vec<DynTool>`.
+pub struct DynTool {
+    definition: ToolDefinition,
+    call: Box<ErasedCall>,
+}
+
+impl DynTool {
+    pub fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    /// Deserialize `args`, run the tool, and serialize its output.
+    ///
+    /// Tools declared with `deny_unknown_fields` reject keys missing from their schema.
+    pub async fn call(&self, args: Value) -> Result<Value, ToolError> {
+        (self.call)(args).await
+    }
+}
+
+impl<T> From<T> for DynTool
+where
+    T: Tool<Error = ToolError> + ToolMetadata + 'static,
+    T::Args: JsonSchema + 'static,
+{
+    fn from(tool: T) -> Self {
+        let tool = Arc::new(tool);
+        DynTool {
+            // `name()` rather than `NAME`, so runtime names are kept
+            definition: tool_definition(&tool.name(), T::DESCRIPTION, T::parameters_schema()),
+            call: Box::new(move |args| {
+                let tool = Arc::clone(&tool);
+                Box::pin(async move { call_json(&*tool, args).await })
+            }),
+        }
+    }
+}
+
+/// Package a yart tool as its definition and a JSON callable, for executors other
+/// than rig.
+///
+/// The callable validates and deserializes args like [`DynTool::call`].
+pub fn prepared<T>(tool: T) -> (ToolDefinition, Box<ErasedCall>)
+where
+    T: Tool<Error = ToolError> + ToolMetadata + 'static,
+    T::Args: JsonSchema + 'static,
+{
+    let DynTool { definition, call } = DynTool::from(tool);
+    (definition, call)
+}
+
+/// Run a typed tool on JSON args, returning its serialized output.
+pub(crate) async fn call_json<T>(tool: &T, args: Value) -> Result<Value, ToolError>
+where
+    T: Tool<Error = ToolError> + ToolMetadata,
+    T::Args: JsonSchema,
+{
+    if T::DENY_UNKNOWN_FIELDS {
+        check_unknown_fields(&args, &T::parameters_schema())?;
+    }
+    let args = deserialize_args::<T::Args>(args)?;
+    let output = tool.call(args).await?;
+    serde_json::to_value(output)
+        .map_err(|e| ToolError::serialization(format!("Serialization error: {}", e)))
+}
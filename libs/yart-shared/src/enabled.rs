@@ -0,0 +1,80 @@
+//! Runtime switch for turning tools off without recompiling.
+//!
+//! Generated tools consult [`enabled_tools`] before every `call` and fail with
+//! "tool disabled" when their name is switched off. The set starts from two
+//! comma-separated env vars, read on first use: `YART_ENABLED_TOOLS` limits the
+//! tools to the listed names, and `YART_DISABLED_TOOLS` switches the listed ones off.
+//! Without either, every tool is enabled.
+
+use std::collections::HashSet;
+use std::sync::{OnceLock, RwLock};
+
+#[derive(Default)]
+struct State {
+    // `None` allows every tool not in `disabled`
+    allowed: Option<HashSet<String>>,
+    disabled: HashSet<String>,
+}
+
+/// The process-wide set of enabled tools, by tool name.
+pub struct EnabledTools {
+    state: RwLock<State>,
+}
+
+fn names_from_env(var: &str) -> Option<HashSet<String>> {
+    let value = std::env::var(var).ok()?;
+    Some(
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+impl EnabledTools {
+    fn from_env() -> Self {
+        EnabledTools {
+            state: RwLock::new(State {
+                allowed: names_from_env("YART_ENABLED_TOOLS"),
+                disabled: names_from_env("YART_DISABLED_TOOLS").unwrap_or_default(),
+            }),
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        !state.disabled.contains(name)
+            && state
+                .allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(name))
+    }
+
+    /// Enable `name`, adding it to the allowed tools if the set is limited.
+    pub fn enable(&self, name: &str) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.disabled.remove(name);
+        if let Some(allowed) = &mut state.allowed {
+            allowed.insert(name.to_string());
+        }
+    }
+
+    pub fn disable(&self, name: &str) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.disabled.insert(name.to_string());
+    }
+
+    /// Enable every tool, dropping the limits set so far (including the env vars').
+    pub fn enable_all(&self) {
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) = State::default();
+    }
+}
+
+static ENABLED_TOOLS: OnceLock<EnabledTools> = OnceLock::new();
+
+/// The enabled tool set shared by all generated tools.
+pub fn enabled_tools() -> &'static EnabledTools {
+    ENABLED_TOOLS.get_or_init(EnabledTools::from_env)
+}
@@ -0,0 +1,89 @@
+//! Name-keyed dispatch over differently-typed tools.
+
+use crate::dyn_tool::call_json;
+use crate::{tool_definition, DynTool, ToolError, ToolMetadata};
+use futures::future::BoxFuture;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// An object-safe tool taking and returning JSON, for storing tools as
+/// `Arc<dyn ErasedTool>`. Every yart tool and [`DynTool`] implements it.
+pub trait ErasedTool: Send + Sync {
+    fn name(&self) -> String;
+
+    fn definition(&self) -> ToolDefinition;
+
+    /// Deserialize `args`, run the tool, and serialize its output.
+    fn call_json(&self, args: Value) -> BoxFuture<'_, Result<Value, ToolError>>;
+}
+
+impl<T> ErasedTool for T
+where
+    T: Tool<Error = ToolError> + ToolMetadata,
+    T::Args: JsonSchema,
+{
+    fn name(&self) -> String {
+        Tool::name(self)
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        tool_definition(&Tool::name(self), T::DESCRIPTION, T::parameters_schema())
+    }
+
+    fn call_json(&self, args: Value) -> BoxFuture<'_, Result<Value, ToolError>> {
+        Box::pin(call_json(self, args))
+    }
+}
+
+impl ErasedTool for DynTool {
+    fn name(&self) -> String {
+        DynTool::definition(self).name
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        DynTool::definition(self)
+    }
+
+    fn call_json(&self, args: Value) -> BoxFuture<'_, Result<Value, ToolError>> {
+        Box::pin(self.call(args))
+    }
+}
+
+/// Tools keyed by name, dispatching JSON calls to the tool the model picked.
+#[derive(Clone, Default)]
+pub struct Registry {
+    tools: BTreeMap<String, Arc<dyn ErasedTool>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Add `tool` under its name, returning the tool it replaced, if any.
+    pub fn register(&mut self, tool: impl ErasedTool + 'static) -> Option<Arc<dyn ErasedTool>> {
+        self.tools.insert(tool.name(), Arc::new(tool))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ErasedTool>> {
+        self.tools.get(name).cloned()
+    }
+
+    /// Call the tool registered as `name`; an unknown name is a validation error,
+    /// since it is the model's to correct.
+    pub async fn call(&self, name: &str, args: Value) -> Result<Value, ToolError> {
+        match self.tools.get(name) {
+            Some(tool) => tool.call_json(args).await,
+            None => Err(ToolError::validation(format!("Unknown tool `{}`", name))),
+        }
+    }
+
+    /// The definitions of every registered tool, sorted by name.
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools.values().map(|tool| tool.definition()).collect()
+    }
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+
+/// Broad category of a [`ToolError`], used to decide how callers should react.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorKind {
+    /// The tool body failed.
+    #[default]
+    Execution,
+    /// The args didn't match the tool's schema.
+    Validation,
+    /// The tool's output couldn't be serialized.
+    Serialization,
+    /// The tool didn't finish in time.
+    Timeout,
+}
+
+impl ErrorKind {
+    /// Stable lowercase name, e.g. `"validation"`, for reporting errors as data.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Execution => "execution",
+            ErrorKind::Validation => "validation",
+            ErrorKind::Serialization => "serialization",
+            ErrorKind::Timeout => "timeout",
+        }
+    }
+}
+
+/// One place the args violate the tool's schema, as reported by `validate_schema`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// JSON pointer to the offending value, e.g. `/limit`; empty for the args as a whole.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.message)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ToolError {
+    pub message: String,
+    pub tool: Option<String>,
+    pub kind: ErrorKind,
+    /// Set for execution failures that may succeed on retry (e.g. a dropped connection).
+    pub transient: bool,
+    /// Context frames added with [`ToolError::with_context`], innermost first.
+    pub context: Vec<String>,
+    /// ID of the call that failed, shared with its `tool_call` span (`request-id` feature).
+    pub request_id: Option<String>,
+    /// Every schema violation found in the args, so the model can fix them all at once.
+    /// A boxed slice keeps `ToolError` small in the common case of none.
+    pub field_errors: Box<[FieldError]>,
+}
+
+impl ToolError {
+    pub fn new(s: impl Into<String>) -> Self {
+        ToolError {
+            message: s.into(),
+            tool: None,
+            kind: ErrorKind::Execution,
+            transient: false,
+            context: Vec::new(),
+            request_id: None,
+            field_errors: Box::default(),
+        }
+    }
+
+    pub fn validation(s: impl Into<String>) -> Self {
+        ToolError::new(s).with_kind(ErrorKind::Validation)
+    }
+
+    pub fn serialization(s: impl Into<String>) -> Self {
+        ToolError::new(s).with_kind(ErrorKind::Serialization)
+    }
+
+    pub fn timeout(s: impl Into<String>) -> Self {
+        ToolError::new(s).with_kind(ErrorKind::Timeout)
+    }
+
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Mark this error as transient, so [`ToolError::is_retryable`] returns true.
+    pub fn transient(mut self) -> Self {
+        self.transient = true;
+        self
+    }
+
+    /// Whether retrying the same call may succeed: timeouts and transient execution
+    /// failures are retryable, validation and serialization errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            ErrorKind::Timeout => true,
+            ErrorKind::Execution => self.transient,
+            ErrorKind::Validation | ErrorKind::Serialization => false,
+        }
+    }
+
+    /// Wrap the error in a higher-level explanation, shown before the message like
+    /// anyhow's `{:#}` chain: `outer: inner: message`.
+    pub fn with_context(mut self, context: impl std::fmt::Display) -> Self {
+        self.context.push(context.to_string());
+        self
+    }
+
+    /// Attach the ID of the call that produced this error, keeping an existing one.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id.get_or_insert_with(|| request_id.into());
+        self
+    }
+
+    /// Attach the individual violations behind a validation error.
+    pub fn with_field_errors(mut self, field_errors: Vec<FieldError>) -> Self {
+        self.field_errors = field_errors.into_boxed_slice();
+        self
+    }
+
+    /// Attach the name of the tool that produced this error.
+    pub fn with_tool(mut self, name: impl Into<String>) -> Self {
+        self.tool = Some(name.into());
+        self
+    }
+}
+
+impl std::fmt::Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(tool) = &self.tool {
+            write!(f, "[{}] ", tool)?;
+        }
+        for context in self.context.iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+impl From<anyhow::Error> for ToolError {
+    // A bare ToolError (e.g. a timeout from `wrap_unsafe_timeout`) is recovered as
+    // is; otherwise `{:#}` keeps anyhow's whole context chain
+    fn from(e: anyhow::Error) -> Self {
+        if e.chain().count() == 1 {
+            match e.downcast::<ToolError>() {
+                Ok(e) => e,
+                Err(e) => ToolError::new(format!("{:#}", e)),
+            }
+        } else {
+            ToolError::new(format!("{:#}", e))
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for ToolError {
+    fn from(e: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
+        ToolError::new(e.to_string())
+    }
+}
+
+/// Unify errors from rig-native tools on yart's error type.
+///
+/// A yart `ToolError` that rig boxed up (e.g. from a toolset call) is recovered as is.
+/// rig's JSON errors come from parsing the model's args, so they are validation errors.
+impl From<rig::tool::ToolError> for ToolError {
+    fn from(e: rig::tool::ToolError) -> Self {
+        match e {
+            rig::tool::ToolError::ToolCallError(e) => match e.downcast::<ToolError>() {
+                Ok(e) => *e,
+                Err(e) => ToolError::new(e.to_string()),
+            },
+            rig::tool::ToolError::JsonError(e) => ToolError::validation(e.to_string()),
+        }
+    }
+}
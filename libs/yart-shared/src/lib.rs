@@ -1,9 +1,65 @@
 use anyhow::{anyhow, Result};
-use schemars::{schema_for, JsonSchema};
-use serde::{Deserialize, Serialize};
-use serde_json::{to_value, Value};
+use rig::completion::message::{
+    ContentFormat, ImageMediaType, MimeType, ToolResultContent, UserContent,
+};
+use rig::completion::Message;
+use rig::OneOrMany;
+use schemars::schema_for;
+use serde::{de::DeserializeOwned, ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::{json, to_value, Map, Value};
+use serde_path_to_error::Segment;
 use std::future::Future;
-use tokio::{spawn, sync::mpsc};
+use std::time::Duration;
+use tokio::{spawn, sync::mpsc, task};
+
+pub mod batch;
+pub mod catalog;
+#[cfg(feature = "clap")]
+pub mod cli;
+mod diff;
+mod dyn_tool;
+mod enabled;
+mod erased;
+mod error;
+pub mod mcp;
+pub mod metrics;
+pub mod middleware;
+pub mod mock;
+pub mod openapi;
+mod partial;
+mod pipeline;
+#[cfg(feature = "rate-limit")]
+mod rate_limit;
+#[cfg(feature = "registry")]
+pub mod registry;
+mod rename;
+mod stream;
+pub mod test_support;
+#[cfg(feature = "tower")]
+pub mod tower;
+
+pub use bytes::Bytes;
+pub use diff::{schema_diff, SchemaChange};
+pub use dyn_tool::{prepared, DynTool, ErasedCall};
+pub use enabled::{enabled_tools, EnabledTools};
+pub use erased::{ErasedTool, Registry};
+pub use error::{ErrorKind, FieldError, ToolError};
+pub use partial::PartialResult;
+pub use pipeline::{pipeline, Pipeline};
+#[cfg(feature = "rate-limit")]
+pub use rate_limit::RateLimiter;
+#[cfg(feature = "registry")]
+pub use registry::{all_tool_descriptors, ToolDescriptor};
+pub use rename::{rename_keys, RenameRule};
+pub use schemars::JsonSchema;
+// Lets `#[derive(ToolArgs)]` expand without a direct schemars dependency
+#[doc(hidden)]
+pub use schemars;
+pub use stream::ToolOutputStream;
+#[cfg(feature = "stream")]
+pub use stream::{collect_text, StreamingTool, TextStream};
+pub use tokio::sync::{OnceCell, Semaphore};
+pub use tracing;
 
 pub async fn wrap_unsafe<F, Fut, T>(f: F) -> Result<T>
 where
@@ -11,50 +67,757 @@ where
     Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
-    let (tx, mut rx) = mpsc::channel(1);
+    wrap_unsafe_with_capacity(1, f).await
+}
+
+/// Like [`wrap_unsafe`], with the capacity of the channel the result is sent over.
+///
+/// The task sends exactly one value, so capacity 1 never makes it wait; a larger
+/// buffer only reserves more memory. It is exposed for callers sharing one capacity
+/// setting with channel-based streaming code. Panics if `capacity` is 0.
+pub async fn wrap_unsafe_with_capacity<F, Fut, T>(capacity: usize, f: F) -> Result<T>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel(capacity);
 
-    spawn(async move {
+    let handle = spawn(async move {
         let result = f().await;
         let _ = tx.send(result).await;
     });
 
-    rx.recv().await.ok_or_else(|| anyhow!("Channel closed"))?
+    match rx.recv().await {
+        Some(result) => result,
+        // The task was dropped before sending, e.g. by runtime shutdown
+        None => match handle.await {
+            Err(e) if e.is_cancelled() => Err(anyhow!("tool task aborted")),
+            _ => Err(anyhow!("Channel closed")),
+        },
+    }
 }
 
-#[derive(Debug)]
-pub struct ToolError(pub String);
-
-impl ToolError {
-    pub fn new(s: impl Into<String>) -> Self {
-        ToolError(s.into())
+/// Like [`wrap_unsafe`], but aborts the task if it hasn't finished within `timeout`.
+///
+/// A timeout is returned as a [`ToolError`] of kind [`ErrorKind::Timeout`], which
+/// converting the `anyhow::Error` back into a `ToolError` recovers.
+pub async fn wrap_unsafe_timeout<F, Fut, T>(timeout: Duration, f: F) -> Result<T>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let handle = spawn(async move { f().await });
+    let abort = handle.abort_handle();
+    match tokio::time::timeout(timeout, handle).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) if e.is_cancelled() => Err(anyhow!("tool task aborted")),
+        Ok(Err(e)) => Err(anyhow!("tool task failed: {}", e)),
+        Err(_) => {
+            abort.abort();
+            Err(ToolError::timeout(format!("Timed out after {}ms", timeout.as_millis())).into())
+        }
     }
 }
 
-impl std::fmt::Display for ToolError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.0)
-    }
+/// A fresh random ID for one tool call, used to correlate its span and errors.
+#[cfg(feature = "request-id")]
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Compile-time check that a tool's output can cross out of the task its body
+/// runs on.
+///
+/// Generated tools call this on the return type from the function signature, so a
+/// non-`Send` type like `Rc` is reported at the signature instead of only as a
+/// trait-bound error deep in the macro expansion.
+#[doc(hidden)]
+pub fn assert_send_static<T: Send + 'static>() {}
+
+/// Bound generated tools place on their args type, so a non-`Send` args type is
+/// reported once, at the signature, instead of as a pile of rig trait-bound errors.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "tool args `{Self}` must be `Send + Sync + 'static`",
+    label = "tool args must be `Send + Sync + 'static`",
+    note = "wrap it in Arc/Mutex or make it Send"
+)]
+pub trait SendArgs: Send + Sync + 'static {}
+
+#[diagnostic::do_not_recommend]
+impl<T: Send + Sync + 'static> SendArgs for T {}
+
+#[doc(hidden)]
+pub fn assert_send_args<T: SendArgs>() {}
+
+/// Bound generated tools place on their args type, since rig deserializes the
+/// model's JSON into it.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "tool args `{Self}` must implement `Deserialize`: add `#[derive(Deserialize)]`",
+    label = "the model's JSON is deserialized into the tool args"
+)]
+pub trait DeserializeArgs: DeserializeOwned {}
+
+#[diagnostic::do_not_recommend]
+impl<T: DeserializeOwned> DeserializeArgs for T {}
+
+/// Compile-time check that a tool's args can be deserialized from the model's JSON,
+/// reported at the signature when the args type only derives `Serialize`.
+#[doc(hidden)]
+pub fn assert_args_deserialize<T: DeserializeArgs>() {}
+
+/// Marks args types whose `Deserialize` rejects unknown fields, as tools declared
+/// with `deny_unknown_fields` require.
+///
+/// rig deserializes args before the tool runs, so only `#[serde(deny_unknown_fields)]`
+/// stops extra keys on that path. `#[derive(ToolArgs)]` implements it when the struct
+/// has that attribute; implement it by hand for args with a hand-written schema.
+#[diagnostic::on_unimplemented(
+    message = "tool args `{Self}` must reject unknown fields: add `#[serde(deny_unknown_fields)]` and derive `yart::ToolArgs`",
+    label = "`deny_unknown_fields` requires args that reject unknown fields",
+    note = "rig deserializes the args itself, so only the serde attribute rejects extra keys"
+)]
+pub trait DenyUnknownFields {}
+
+/// Compile-time check that a `deny_unknown_fields` tool's args reject unknown fields.
+#[doc(hidden)]
+pub fn assert_denies_unknown_fields<T: DenyUnknownFields>() {}
+
+/// Bound generated tools place on their context type, since each call clones the
+/// context into the task its body runs on.
+///
+/// Wrap a non-`Clone` context in `Arc`, or store a `&'static` reference to it.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "tool context `{Self}` must be `Clone`: wrap it in Arc or use a &'static reference",
+    label = "each call clones the tool context",
+    note = "wrap it in Arc or use a &'static reference"
+)]
+pub trait CloneContext: Clone {}
+
+#[diagnostic::do_not_recommend]
+impl<T: Clone> CloneContext for T {}
+
+/// [`CloneContext`] checked on `()` rather than the context itself, so the
+/// signature error doesn't suggest borrowing the context, which `&T: Clone` satisfies.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "tool context `{T}` must be `Clone`: wrap it in Arc or use a &'static reference",
+    label = "each call clones the tool context",
+    note = "wrap it in Arc or use a &'static reference"
+)]
+pub trait ContextClone<T> {}
+
+#[diagnostic::do_not_recommend]
+impl<T: CloneContext> ContextClone<T> for () {}
+
+/// Compile-time check that a tool context can be cloned into each call.
+#[doc(hidden)]
+pub fn assert_context_clone<T>()
+where
+    (): ContextClone<T>,
+{
 }
 
-impl std::error::Error for ToolError {}
+/// Clone the context for one call of a generated tool.
+#[doc(hidden)]
+pub fn clone_context<T: CloneContext>(ctx: &T) -> T {
+    ctx.clone()
+}
 
-impl From<anyhow::Error> for ToolError {
-    fn from(e: anyhow::Error) -> Self {
-        ToolError(e.to_string())
+/// Run a synchronous, CPU-bound tool body on tokio's blocking thread pool so it
+/// doesn't starve the async runtime. Panics in `f` are returned as errors.
+pub async fn wrap_blocking<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => {
+            let payload = e.into_panic();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(anyhow!("Blocking task panicked: {}", message))
+        }
+        Err(e) => Err(anyhow!("Blocking task failed: {}", e)),
     }
 }
 
-impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for ToolError {
-    fn from(e: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
-        ToolError(e.to_string())
+/// The args type of a tool, e.g. `ArgsOf<SearchTool>`.
+///
+/// Stable Rust has no inherent associated types, so `SearchTool::Args` can't be
+/// written for a concrete tool; this alias is the shorthand for
+/// `<SearchTool as rig::tool::Tool>::Args`.
+pub type ArgsOf<T> = <T as rig::tool::Tool>::Args;
+
+/// A builder that tools can be added to, such as rig's `AgentBuilder`.
+///
+/// Generated tools use this in `register`, so `SearchTool::new(ctx).register(builder)`
+/// works with any builder implementing it.
+pub trait ToolBuilder: Sized {
+    fn tool<T: rig::tool::Tool + 'static>(self, tool: T) -> Self;
+}
+
+impl<M: rig::completion::CompletionModel> ToolBuilder for rig::agent::AgentBuilder<M> {
+    fn tool<T: rig::tool::Tool + 'static>(self, tool: T) -> Self {
+        rig::agent::AgentBuilder::tool(self, tool)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Static tool metadata generated by `#[rig_tool]`, available without an instance.
+pub trait ToolMetadata {
+    const NAME: &'static str;
+    const DESCRIPTION: &'static str;
+    /// Whether args with keys missing from the schema's `properties` are rejected.
+    const DENY_UNKNOWN_FIELDS: bool = false;
+
+    fn parameters_schema() -> Value;
+}
+
+#[derive(Debug, Deserialize)]
 pub struct ToolOutput {
     pub result: Value,
+    /// Typed content parts for multimodal output; omitted when empty.
+    #[serde(default)]
+    pub content: Vec<Content>,
+    /// Metadata about the result, such as an HTTP status; omitted when empty.
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+    /// Key the result is serialized under; `"result"` when unset.
+    #[serde(skip)]
+    pub result_key: Option<&'static str>,
 }
 
-pub fn derive_parameters<T: JsonSchema + for<'de> Deserialize<'de>>() -> serde_json::Value {
+impl ToolOutput {
+    pub fn new(result: Value) -> Self {
+        ToolOutput {
+            result,
+            content: Vec::new(),
+            meta: Map::new(),
+            result_key: None,
+        }
+    }
+
+    /// Report `error` as a successful result the model can read and react to:
+    /// `{"error": "...", "kind": "validation"}`, plus `field_errors` when the args
+    /// failed schema validation.
+    pub fn from_error(error: &ToolError) -> Self {
+        let mut result = json!({
+            "error": error.to_string(),
+            "kind": error.kind.as_str(),
+        });
+        if let Some(request_id) = &error.request_id {
+            result["request_id"] = json!(request_id);
+        }
+        if !error.field_errors.is_empty() {
+            result["field_errors"] = json!(error.field_errors);
+        }
+        ToolOutput::new(result)
+    }
+
+    /// Attach a metadata entry, e.g. `with_meta("status", 200)`.
+    pub fn with_meta(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.meta.insert(key.into(), value.into());
+        self
+    }
+
+    /// Serialize the result under `key` instead of `"result"`.
+    pub fn with_result_key(mut self, key: &'static str) -> Self {
+        self.result_key = Some(key);
+        self
+    }
+
+    /// Append a content part, e.g. an image produced alongside the JSON result.
+    pub fn with_content(mut self, content: Content) -> Self {
+        self.content.push(content);
+        self
+    }
+
+    /// Build the tool-result message for the tool call `id`, to feed the output back
+    /// into a conversation.
+    ///
+    /// The serialized output is sent as text, as rig does for tool calls it runs
+    /// itself; image content parts are also attached as images.
+    pub fn to_message(&self, id: impl Into<String>) -> Message {
+        let text = serde_json::to_string(self).expect("Failed to serialize tool output");
+        let mut content = OneOrMany::one(ToolResultContent::text(text));
+        for part in &self.content {
+            if let Content::Image { mime, data_base64 } = part {
+                content.push(ToolResultContent::image(
+                    data_base64.clone(),
+                    Some(ContentFormat::Base64),
+                    ImageMediaType::from_mime_type(mime),
+                    None,
+                ));
+            }
+        }
+        Message::User {
+            content: OneOrMany::one(UserContent::tool_result(id, content)),
+        }
+    }
+}
+
+impl Serialize for ToolOutput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = 1 + usize::from(!self.content.is_empty()) + usize::from(!self.meta.is_empty());
+        let mut map = serializer.serialize_map(Some(len))?;
+        map.serialize_entry(self.result_key.unwrap_or("result"), &self.result)?;
+        if !self.content.is_empty() {
+            map.serialize_entry("content", &self.content)?;
+        }
+        if !self.meta.is_empty() {
+            map.serialize_entry("meta", &self.meta)?;
+        }
+        map.end()
+    }
+}
+
+/// A typed piece of tool output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Content {
+    Text(String),
+    Json(Value),
+    Image { mime: String, data_base64: String },
+}
+
+/// Generate the JSON schema for a tool's arguments.
+///
+/// Fields with `#[serde(default)]` are left out of `required` and carry their default
+/// value under the `default` keyword, so the model knows what an omitted field means.
+pub fn derive_parameters<T: JsonSchema>() -> serde_json::Value {
     to_value(schema_for!(T)).expect("Failed to serialize schema")
 }
+
+/// Generate the JSON schema of a tool's output type, e.g. for docs and prompts.
+pub fn derive_output_schema<T: JsonSchema>() -> serde_json::Value {
+    to_value(schema_for!(T)).expect("Failed to serialize schema")
+}
+
+/// Like [`derive_parameters`], but replaces the schema `title` (the Rust type name)
+/// with `title`.
+pub fn derive_parameters_titled<T: JsonSchema>(title: &str) -> serde_json::Value {
+    let mut schema = derive_parameters::<T>();
+    schema["title"] = Value::String(title.to_string());
+    schema
+}
+
+/// Like [`derive_parameters`], but sets the root `$id` to `id`, e.g. the schema's
+/// URL in a schema registry.
+pub fn derive_parameters_with_id<T: JsonSchema>(id: &str) -> serde_json::Value {
+    derive_parameters_with::<T>(|schema| schema["$id"] = Value::String(id.to_string()))
+}
+
+/// Like [`derive_parameters`], but lets `f` adjust the schema, e.g. to strip
+/// keywords a provider rejects.
+pub fn derive_parameters_with<T: JsonSchema>(f: impl FnOnce(&mut Value)) -> serde_json::Value {
+    let mut schema = derive_parameters::<T>();
+    f(&mut schema);
+    schema
+}
+
+/// Like [`derive_parameters`], but rendered as YAML, e.g. for schemas kept in
+/// human-edited config files.
+#[cfg(feature = "yaml")]
+pub fn derive_parameters_yaml<T: JsonSchema>() -> String {
+    serde_yaml::to_string(&derive_parameters::<T>()).expect("Failed to serialize schema")
+}
+
+/// Like [`derive_parameters`], but without `format` keywords (e.g. `"int32"`), which
+/// some providers reject because they only understand base JSON types.
+pub fn derive_parameters_no_format<T: JsonSchema>() -> serde_json::Value {
+    derive_parameters_with::<T>(|schema| remove_format(schema, false))
+}
+
+// Remove `format` keywords at any depth. Keys of `properties` and `definitions` are
+// names rather than keywords, so a field called `format` is kept.
+fn remove_format(value: &mut Value, names: bool) {
+    match value {
+        Value::Object(map) => {
+            if !names {
+                map.remove("format");
+            }
+            for (key, child) in map.iter_mut() {
+                let child_names = !names && (key == "properties" || key == "definitions");
+                remove_format(child, child_names);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| remove_format(item, false)),
+        _ => {}
+    }
+}
+
+/// Build a rig `ToolDefinition`.
+///
+/// Generated code goes through this instead of a struct literal, so when rig adds
+/// fields to `ToolDefinition` only this function needs updating, not every tool.
+pub fn tool_definition(
+    name: &str,
+    description: &str,
+    parameters: Value,
+) -> rig::completion::ToolDefinition {
+    rig::completion::ToolDefinition {
+        name: name.to_string(),
+        description: description.to_string(),
+        parameters,
+    }
+}
+
+/// Wrap several tools' parameter schemas into a single
+/// `{ "tools": [{ "name", "parameters" }] }` envelope for batched function calling.
+pub fn combined_schema(tools: &[(&str, Value)]) -> Value {
+    let tools: Vec<Value> = tools
+        .iter()
+        .map(|(name, parameters)| json!({ "name": name, "parameters": parameters }))
+        .collect();
+    json!({ "tools": tools })
+}
+
+/// Check a toolset for tool names used more than once, which providers resolve by
+/// silently keeping one of the tools.
+///
+/// Returns each duplicated name once, in the order its second use appears.
+pub fn validate_unique_names(names: &[&str]) -> Result<(), Vec<String>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut duplicates: Vec<String> = Vec::new();
+    for name in names {
+        if !seen.insert(*name) && !duplicates.iter().any(|d| d == name) {
+            duplicates.push(name.to_string());
+        }
+    }
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(duplicates)
+    }
+}
+
+/// Deserialize tool arguments from raw JSON, reporting which field failed and the
+/// type the schema expects so the model can correct its next call.
+///
+/// Some providers send a bare value (e.g. `"hi"`) for args with a single field. If
+/// that fails to deserialize, it is retried wrapped as `{ "<field>": value }`.
+pub fn deserialize_args<T: JsonSchema + DeserializeOwned>(value: Value) -> Result<T, ToolError> {
+    let wrapped = if value.is_object() {
+        None
+    } else {
+        single_field(&derive_parameters::<T>()).map(|field| json!({ field: value.clone() }))
+    };
+    match serde_path_to_error::deserialize(value) {
+        Ok(args) => Ok(args),
+        Err(e) => match wrapped.and_then(|wrapped| serde_json::from_value(wrapped).ok()) {
+            Some(args) => Ok(args),
+            None => Err(invalid_args::<T>(e)),
+        },
+    }
+}
+
+// The only property of an object schema, if it has exactly one.
+fn single_field(schema: &Value) -> Option<String> {
+    let properties = schema.get("properties")?.as_object()?;
+    match properties.keys().collect::<Vec<_>>()[..] {
+        [field] => Some(field.clone()),
+        _ => None,
+    }
+}
+
+// Explain a failed deserialization in terms of the args schema.
+fn invalid_args<T: JsonSchema + DeserializeOwned>(
+    e: serde_path_to_error::Error<serde_json::Error>,
+) -> ToolError {
+    let mut segments: Vec<String> = e
+        .path()
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Seq { index } => Some(index.to_string()),
+            Segment::Map { key } => Some(key.clone()),
+            Segment::Enum { variant } => Some(variant.clone()),
+            Segment::Unknown => None,
+        })
+        .collect();
+    let error = e.into_inner().to_string();
+    let schema = derive_parameters::<T>();
+
+    let message = match missing_field(&error) {
+        Some(field) => {
+            segments.push(field.to_string());
+            format!(
+                "missing required field `{}` (expected {})",
+                segments.join("."),
+                expected_type(&schema, &segments)
+            )
+        }
+        None if segments.is_empty() => error,
+        None => format!(
+            "field `{}` is invalid: {} (expected {})",
+            segments.join("."),
+            error,
+            expected_type(&schema, &segments)
+        ),
+    };
+    ToolError::validation(format!(
+        "Invalid arguments: {}. Call the tool again with arguments matching its parameter schema.",
+        message
+    ))
+}
+
+/// Replace the value of every object key listed in `secrets` with `"***"`, at any
+/// depth, so args can be logged without leaking credentials or PII.
+pub fn redact(value: Value, secrets: &[&str]) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if secrets.contains(&key.as_str()) {
+                        (key, Value::String("***".to_string()))
+                    } else {
+                        (key, redact(value, secrets))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| redact(item, secrets))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Remove every object key whose value is `null`, at any depth. `null` items of
+/// arrays are kept, since dropping them would shift the positions of the rest.
+pub fn remove_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, remove_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(remove_nulls).collect()),
+        other => other,
+    }
+}
+
+/// Reject top-level keys of `value` that aren't declared in the schema's `properties`.
+///
+/// serde ignores unknown fields by default, so this catches args the model made up.
+pub fn check_unknown_fields(value: &Value, schema: &Value) -> Result<(), ToolError> {
+    let (Some(args), Some(properties)) = (
+        value.as_object(),
+        schema.get("properties").and_then(Value::as_object),
+    ) else {
+        return Ok(());
+    };
+    let unknown: Vec<&str> = args
+        .keys()
+        .filter(|key| !properties.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let expected: Vec<String> = properties.keys().map(|key| format!("`{}`", key)).collect();
+    Err(ToolError::validation(format!(
+        "Invalid arguments: unknown field(s) {} (expected only {}). \
+         Call the tool again with arguments matching its parameter schema.",
+        unknown
+            .iter()
+            .map(|key| format!("`{}`", key))
+            .collect::<Vec<_>>()
+            .join(", "),
+        expected.join(", ")
+    )))
+}
+
+/// Validate `value` against a compiled JSON schema, including keywords serde doesn't
+/// check such as `minimum`, `maximum` and `pattern`.
+///
+/// Every violation is listed in the returned validation error.
+#[cfg(feature = "validate-schema")]
+pub fn validate_schema(value: &Value, validator: &SchemaValidator) -> Result<(), ToolError> {
+    let violations = validator
+        .violations(value)
+        .map_err(|e| ToolError::new(format!("Invalid parameter schema: {}", e)))?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let joined: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    Err(ToolError::validation(format!(
+        "Invalid arguments: {}. \
+         Call the tool again with arguments matching its parameter schema.",
+        joined.join("; ")
+    ))
+    .with_field_errors(violations))
+}
+
+/// Check a tool's serialized output against its own output schema.
+///
+/// A mismatch is a bug in the tool rather than in the model's call, so it is
+/// reported as an execution error.
+#[cfg(feature = "validate-output")]
+pub fn validate_output(value: &Value, validator: &SchemaValidator) -> Result<(), ToolError> {
+    let violations = validator
+        .violations(value)
+        .map_err(|e| ToolError::new(format!("Invalid output schema: {}", e)))?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let joined: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    Err(ToolError::new(format!(
+        "Output doesn't match the output schema: {}",
+        joined.join("; ")
+    )))
+}
+
+/// Checks the output of a generated tool against the schema of `$output`, compiled
+/// once per tool.
+///
+/// Expands to nothing without yart's `validate-output` feature, so the check follows
+/// the features yart is built with.
+#[cfg(feature = "validate-output")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __validate_output {
+    ($output:ty, $value:expr, $tool_name:expr) => {{
+        static OUTPUT_VALIDATOR: std::sync::OnceLock<Option<$crate::SchemaValidator>> =
+            std::sync::OnceLock::new();
+        let validator = OUTPUT_VALIDATOR.get_or_init(|| {
+            use $crate::{HasOutputSchema as _, NoOutputSchema as _};
+            (&$crate::OutputSchemaProbe::<$output>::new())
+                .output_schema()
+                .map(|schema| $crate::SchemaValidator::new(&schema))
+        });
+        if let Some(validator) = validator {
+            $crate::validate_output($value, validator).map_err(|e| e.with_tool($tool_name))?;
+        }
+    }};
+}
+
+#[cfg(not(feature = "validate-output"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __validate_output {
+    ($output:ty, $value:expr, $tool_name:expr) => {};
+}
+
+/// A JSON schema compiled once, for checks that run on every call.
+#[cfg(any(feature = "validate-schema", feature = "validate-output"))]
+pub struct SchemaValidator(Result<jsonschema::Validator, String>);
+
+#[cfg(any(feature = "validate-schema", feature = "validate-output"))]
+impl SchemaValidator {
+    /// Compile `schema`; an invalid schema is reported by every check against it.
+    pub fn new(schema: &Value) -> Self {
+        SchemaValidator(jsonschema::validator_for(schema).map_err(|e| e.to_string()))
+    }
+
+    // Describe every place `value` violates the schema, with its JSON pointer.
+    fn violations(&self, value: &Value) -> Result<Vec<FieldError>, &str> {
+        let validator = self.0.as_ref().map_err(String::as_str)?;
+        Ok(validator
+            .iter_errors(value)
+            .map(|error| FieldError {
+                path: error.instance_path().to_string(),
+                message: error.to_string(),
+            })
+            .collect())
+    }
+}
+
+/// Selects the output schema of `T` when `T: JsonSchema`, and `None` otherwise.
+///
+/// Generated tools call `(&OutputSchemaProbe::<Output>::new()).output_schema()`
+/// with both traits in scope; method resolution picks [`HasOutputSchema`] when its
+/// bound holds and falls back to [`NoOutputSchema`] through the extra reference.
+#[cfg(feature = "validate-output")]
+#[doc(hidden)]
+pub struct OutputSchemaProbe<T>(std::marker::PhantomData<fn() -> T>);
+
+#[cfg(feature = "validate-output")]
+impl<T> OutputSchemaProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        OutputSchemaProbe(std::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "validate-output")]
+#[doc(hidden)]
+pub trait HasOutputSchema {
+    fn output_schema(&self) -> Option<Value>;
+}
+
+#[cfg(feature = "validate-output")]
+impl<T: JsonSchema> HasOutputSchema for OutputSchemaProbe<T> {
+    fn output_schema(&self) -> Option<Value> {
+        Some(derive_output_schema::<T>())
+    }
+}
+
+#[cfg(feature = "validate-output")]
+#[doc(hidden)]
+pub trait NoOutputSchema {
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+}
+
+#[cfg(feature = "validate-output")]
+impl<T> NoOutputSchema for &OutputSchemaProbe<T> {}
+
+// Extract the field name from serde's "missing field `name`" message.
+fn missing_field(error: &str) -> Option<&str> {
+    error
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+}
+
+// Walk the schema along `path` and describe the type found there.
+fn expected_type(schema: &Value, path: &[String]) -> String {
+    let mut current = schema;
+    for segment in path {
+        current = resolve_ref(schema, current);
+        current = match current
+            .get("properties")
+            .and_then(|properties| properties.get(segment))
+            .or_else(|| current.get("items"))
+        {
+            Some(next) => next,
+            None => return "a value matching the schema".to_string(),
+        };
+    }
+    let current = resolve_ref(schema, current);
+    match current.get("type") {
+        Some(Value::String(ty)) => ty.clone(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        _ => current
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or("a value matching the schema")
+            .to_string(),
+    }
+}
+
+// Follow a local `#/definitions/...` reference, if any.
+fn resolve_ref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+        .and_then(|name| root.get("definitions").and_then(|defs| defs.get(name)))
+        .unwrap_or(schema)
+}
@@ -0,0 +1,13 @@
+//! Model Context Protocol (MCP) interop.
+
+use crate::ToolMetadata;
+use serde_json::{json, Value};
+
+/// Build an MCP `tools/list` entry (`name`, `description`, `inputSchema`) for a tool.
+pub fn descriptor<T: ToolMetadata>() -> Value {
+    json!({
+        "name": T::NAME,
+        "description": T::DESCRIPTION,
+        "inputSchema": T::parameters_schema(),
+    })
+}
@@ -0,0 +1,40 @@
+//! Pluggable per-tool metrics.
+//!
+//! Generated tools report every `call` to the installed [`Recorder`]; without one,
+//! a no-op recorder is used.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Receives one record per tool call.
+pub trait Recorder: Send + Sync {
+    fn record(&self, name: &str, duration: Duration, ok: bool);
+}
+
+/// The default recorder, which discards every record.
+pub struct NoopRecorder;
+
+impl Recorder for NoopRecorder {
+    fn record(&self, _name: &str, _duration: Duration, _ok: bool) {}
+}
+
+static RECORDER: RwLock<Option<Arc<dyn Recorder>>> = RwLock::new(None);
+
+/// Install `recorder` for all tools, replacing any previous one.
+pub fn set_recorder(recorder: impl Recorder + 'static) {
+    *RECORDER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(recorder));
+}
+
+/// The installed recorder, or [`NoopRecorder`] if none is set.
+pub fn recorder() -> Arc<dyn Recorder> {
+    RECORDER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| Arc::new(NoopRecorder))
+}
+
+/// Report a finished call to the installed recorder.
+pub fn record(name: &str, duration: Duration, ok: bool) {
+    recorder().record(name, duration, ok);
+}
@@ -0,0 +1,38 @@
+//! Stacks of output transformations applied after a tool runs.
+
+use crate::ToolError;
+use rig::tool::Tool;
+use serde_json::Value;
+
+/// One step in a chain of output transformations, e.g. redacting fields or
+/// renaming keys.
+pub trait Transform: Send + Sync {
+    fn apply(&self, value: Value) -> Result<Value, ToolError>;
+}
+
+impl<F> Transform for F
+where
+    F: Fn(Value) -> Result<Value, ToolError> + Send + Sync,
+{
+    fn apply(&self, value: Value) -> Result<Value, ToolError> {
+        self(value)
+    }
+}
+
+/// Call `tool` and pass its serialized output through each of `transforms` in
+/// order, stopping at the first error.
+pub async fn call_with_middleware<T>(
+    tool: &T,
+    args: T::Args,
+    transforms: &[Box<dyn Transform>],
+) -> Result<Value, ToolError>
+where
+    T: Tool<Error = ToolError>,
+{
+    let output = tool.call(args).await?;
+    let value = serde_json::to_value(output)
+        .map_err(|e| ToolError::serialization(format!("Serialization error: {}", e)))?;
+    transforms
+        .iter()
+        .try_fold(value, |value, transform| transform.apply(value))
+}
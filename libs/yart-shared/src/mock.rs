@@ -0,0 +1,44 @@
+//! Stand-ins for real tools when testing agent logic.
+
+use crate::{tool_definition, ToolError, ToolMetadata, ToolOutput};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+/// A tool with the same name, description, args, and schema as `T` whose calls
+/// return a canned `response` instead of running `T`'s body, e.g.
+/// `MockTool::<SearchTool>::new(json!(["doc-1"]))`.
+pub struct MockTool<T> {
+    response: Value,
+    _tool: PhantomData<fn() -> T>,
+}
+
+impl<T: Tool + ToolMetadata> MockTool<T> {
+    pub fn new(response: Value) -> Self {
+        MockTool {
+            response,
+            _tool: PhantomData,
+        }
+    }
+}
+
+impl<T: Tool + ToolMetadata> Tool for MockTool<T> {
+    const NAME: &'static str = <T as ToolMetadata>::NAME;
+
+    type Error = ToolError;
+    type Args = T::Args;
+    type Output = ToolOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        tool_definition(
+            <T as ToolMetadata>::NAME,
+            <T as ToolMetadata>::DESCRIPTION,
+            T::parameters_schema(),
+        )
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(ToolOutput::new(self.response.clone()))
+    }
+}
@@ -0,0 +1,20 @@
+//! OpenAPI interop.
+
+use crate::ToolMetadata;
+use serde_json::{json, Value};
+
+/// Build an OpenAPI operation object that accepts the tool's args as a JSON request body.
+pub fn operation<T: ToolMetadata>() -> Value {
+    json!({
+        "operationId": T::NAME,
+        "summary": T::DESCRIPTION,
+        "requestBody": {
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": T::parameters_schema(),
+                },
+            },
+        },
+    })
+}
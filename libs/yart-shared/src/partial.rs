@@ -0,0 +1,77 @@
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+use crate::ToolError;
+
+/// Output of a batch-style tool where some items may fail without failing the call.
+///
+/// Return it from a body as `Ok(PartialResult)`, collecting per-item results with
+/// `collect()` or [`PartialResult::push`]. It serializes as
+/// `{"succeeded": [...], "failed": [{"error": "...", "kind": "execution"}]}`, so the
+/// model sees which items to retry.
+#[derive(Debug)]
+pub struct PartialResult<T> {
+    pub succeeded: Vec<T>,
+    pub failed: Vec<ToolError>,
+}
+
+impl<T> PartialResult<T> {
+    pub fn new() -> Self {
+        PartialResult {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    /// Record the outcome of one item.
+    pub fn push(&mut self, result: Result<T, ToolError>) {
+        match result {
+            Ok(value) => self.succeeded.push(value),
+            Err(e) => self.failed.push(e),
+        }
+    }
+
+    /// Whether every item succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl<T> Default for PartialResult<T> {
+    fn default() -> Self {
+        PartialResult::new()
+    }
+}
+
+impl<T> FromIterator<Result<T, ToolError>> for PartialResult<T> {
+    fn from_iter<I: IntoIterator<Item = Result<T, ToolError>>>(iter: I) -> Self {
+        let mut partial = PartialResult::new();
+        iter.into_iter().for_each(|result| partial.push(result));
+        partial
+    }
+}
+
+impl<T: Serialize> Serialize for PartialResult<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("succeeded", &self.succeeded)?;
+        map.serialize_entry("failed", &FailedItems(&self.failed))?;
+        map.end()
+    }
+}
+
+// Errors aren't `Serialize`, so report each as its message and kind
+struct FailedItems<'a>(&'a [ToolError]);
+
+impl Serialize for FailedItems<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for error in self.0 {
+            seq.serialize_element(&serde_json::json!({
+                "error": error.to_string(),
+                "kind": error.kind.as_str(),
+            }))?;
+        }
+        seq.end()
+    }
+}
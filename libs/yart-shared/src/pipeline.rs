@@ -0,0 +1,55 @@
+//! Chaining two tools, so one feeds its output to the other.
+
+use crate::ToolError;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde_json::Value;
+
+/// A tool that runs `A`, maps its serialized output to `B`'s args, and returns
+/// `B`'s output. Built with [`pipeline`].
+///
+/// It takes `A`'s name, args, and definition, since those are what the model calls.
+pub struct Pipeline<A, B, F> {
+    first: A,
+    second: B,
+    map: F,
+}
+
+/// Combine `a` and `b` into one tool, with `map` turning `a`'s serialized output
+/// into `b`'s args.
+pub fn pipeline<A, B, F>(a: A, b: B, map: F) -> Pipeline<A, B, F>
+where
+    A: Tool<Error = ToolError>,
+    B: Tool<Error = ToolError>,
+    F: Fn(Value) -> B::Args + Send + Sync,
+{
+    Pipeline {
+        first: a,
+        second: b,
+        map,
+    }
+}
+
+impl<A, B, F> Tool for Pipeline<A, B, F>
+where
+    A: Tool<Error = ToolError>,
+    B: Tool<Error = ToolError>,
+    F: Fn(Value) -> B::Args + Send + Sync,
+{
+    const NAME: &'static str = A::NAME;
+
+    type Error = ToolError;
+    type Args = A::Args;
+    type Output = B::Output;
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.first.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let output = self.first.call(args).await?;
+        let value = serde_json::to_value(output)
+            .map_err(|e| ToolError::serialization(format!("Serialization error: {}", e)))?;
+        self.second.call((self.map)(value)).await
+    }
+}
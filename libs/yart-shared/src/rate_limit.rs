@@ -0,0 +1,91 @@
+//! Per-key token buckets behind the `rate_limit` attribute.
+
+use crate::ToolError;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    by_key: BTreeMap<String, Bucket>,
+    swept: Option<Instant>,
+}
+
+/// Allows `capacity` calls per `period` for each key, refilling continuously, so
+/// a burst of up to `capacity` calls is let through at once.
+///
+/// Generated tools keep one in a `static` per tool and key it by the value of
+/// `rate_limit_key`, e.g. the context's API key.
+pub struct RateLimiter {
+    capacity: u32,
+    period: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub const fn new(capacity: u32, period: Duration) -> Self {
+        RateLimiter {
+            capacity,
+            period,
+            buckets: Mutex::new(Buckets {
+                by_key: BTreeMap::new(),
+                swept: None,
+            }),
+        }
+    }
+
+    /// Take a token from `key`'s bucket, or return how long until one is available.
+    pub fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        let capacity = f64::from(self.capacity);
+        let per_second = capacity / self.period.as_secs_f64();
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        // A bucket idle for a whole period is full again, the same as a new one, so
+        // it can be dropped. Sweeping once per period keeps only the keys seen in
+        // the last two periods instead of every key ever seen.
+        if buckets
+            .swept
+            .is_none_or(|swept| now.duration_since(swept) >= self.period)
+        {
+            buckets
+                .by_key
+                .retain(|_, bucket| now.duration_since(bucket.updated) < self.period);
+            buckets.swept = Some(now);
+        }
+        let bucket = buckets.by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+
+    /// Number of keys currently holding a bucket.
+    pub fn tracked_keys(&self) -> usize {
+        let buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        buckets.by_key.len()
+    }
+
+    /// Like [`RateLimiter::try_acquire`], but rejects with a transient [`ToolError`]
+    /// telling the caller when to retry.
+    pub fn check(&self, key: &str) -> Result<(), ToolError> {
+        self.try_acquire(key).map_err(|wait| {
+            ToolError::new(format!(
+                "Rate limit exceeded, retry in {}ms",
+                wait.as_millis().max(1)
+            ))
+            .transient()
+        })
+    }
+}
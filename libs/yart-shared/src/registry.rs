@@ -0,0 +1,73 @@
+//! Process-wide list of the tools declared with `#[rig_tool]`.
+//!
+//! With the `registry` feature, every generated tool submits a [`ToolDescriptor`],
+//! so agents can be assembled from all tools linked into the binary.
+
+use crate::DynTool;
+use serde_json::Value;
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Static description of a generated tool.
+pub struct ToolDescriptor {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Computes the tool's parameter schema.
+    pub parameters: fn() -> Value,
+    /// Builds the tool; only present for tools without a context.
+    pub constructor: Option<fn() -> DynTool>,
+}
+
+inventory::collect!(ToolDescriptor);
+
+impl ToolDescriptor {
+    /// Whether the tool is switched on in [`enabled_tools`](crate::enabled_tools).
+    pub fn is_enabled(&self) -> bool {
+        crate::enabled_tools().is_enabled(self.name)
+    }
+}
+
+/// Every registered tool, in no particular order.
+pub fn all_tool_descriptors() -> Vec<&'static ToolDescriptor> {
+    inventory::iter::<ToolDescriptor>.into_iter().collect()
+}
+
+/// Builds a [`DynTool`] from `T` when `T` converts into one, and is unreachable
+/// otherwise.
+///
+/// Generated descriptors call `(&DynToolProbe::new(Tool::new)).build()` with both
+/// traits in scope. A tool whose args or context fail its bounds already has an
+/// error at its signature, so falling back to [`NotDynTool`] keeps the descriptor
+/// from repeating it.
+#[doc(hidden)]
+pub struct DynToolProbe<T>(fn() -> T);
+
+impl<T> DynToolProbe<T> {
+    pub fn new(constructor: fn() -> T) -> Self {
+        DynToolProbe(constructor)
+    }
+}
+
+#[doc(hidden)]
+pub trait IsDynTool {
+    fn build(&self) -> DynTool;
+}
+
+impl<T> IsDynTool for DynToolProbe<T>
+where
+    DynTool: From<T>,
+{
+    fn build(&self) -> DynTool {
+        DynTool::from((self.0)())
+    }
+}
+
+#[doc(hidden)]
+pub trait NotDynTool {
+    fn build(&self) -> DynTool {
+        unreachable!("tools failing their bounds don't compile")
+    }
+}
+
+impl<T> NotDynTool for &DynToolProbe<T> {}
@@ -0,0 +1,105 @@
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// Key casing applied to tool output, mirroring serde's `rename_all` rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameRule {
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+/// Parses serde-style rule names such as `"camelCase"`.
+impl FromStr for RenameRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        match rule {
+            "lowercase" => Ok(RenameRule::LowerCase),
+            "UPPERCASE" => Ok(RenameRule::UpperCase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebabCase),
+            _ => Err(format!("unknown rename rule `{}`", rule)),
+        }
+    }
+}
+
+impl RenameRule {
+    /// Rename a single key, e.g. `created_at` -> `createdAt` for `CamelCase`.
+    pub fn apply(self, key: &str) -> String {
+        let words = split_words(key);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().to_string() + chars.as_str(),
+            }
+        };
+        match self {
+            RenameRule::LowerCase => words.concat(),
+            RenameRule::UpperCase => words.concat().to_uppercase(),
+            RenameRule::PascalCase => words.iter().map(|w| capitalize(w)).collect(),
+            RenameRule::CamelCase => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+            RenameRule::SnakeCase => words.join("_"),
+            RenameRule::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            RenameRule::KebabCase => words.join("-"),
+            RenameRule::ScreamingKebabCase => words.join("-").to_uppercase(),
+        }
+    }
+}
+
+// Split `some_key`, `someKey` or `some-key` into lowercase words
+fn split_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Recursively rename every object key in `value` according to `rule`.
+pub fn rename_keys(value: Value, rule: RenameRule) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (rule.apply(&key), rename_keys(value, rule)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| rename_keys(item, rule))
+                .collect(),
+        ),
+        other => other,
+    }
+}
@@ -0,0 +1,89 @@
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use std::fmt;
+#[cfg(feature = "stream")]
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{ToolError, ToolOutput};
+
+/// Tool output produced as a stream of byte chunks, for results too large to hold
+/// as a single `Value`.
+///
+/// A tool body returning `ToolOutputStream` gets a generated `call_stream` method
+/// that hands the stream to the caller. rig's `call` concatenates the chunks into a
+/// string result instead.
+pub struct ToolOutputStream {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, ToolError>> + Send>>,
+}
+
+impl ToolOutputStream {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, ToolError>> + Send + 'static,
+    {
+        ToolOutputStream {
+            inner: Box::pin(stream),
+        }
+    }
+
+    /// Read every chunk into one buffer, stopping at the first error.
+    pub async fn concat(mut self) -> Result<Bytes, ToolError> {
+        let mut buffer = BytesMut::new();
+        while let Some(chunk) = self.inner.next().await {
+            buffer.extend_from_slice(&chunk?);
+        }
+        Ok(buffer.freeze())
+    }
+
+    /// Concatenate the chunks into a `ToolOutput` with a string result.
+    pub async fn into_output(self) -> Result<ToolOutput, ToolError> {
+        let bytes = self.concat().await?;
+        let text = String::from_utf8(bytes.to_vec()).map_err(|e| {
+            ToolError::serialization(format!("Stream output is not valid UTF-8: {}", e))
+        })?;
+        Ok(ToolOutput::new(text.into()))
+    }
+}
+
+impl Stream for ToolOutputStream {
+    type Item = Result<Bytes, ToolError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for ToolOutputStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolOutputStream").finish_non_exhaustive()
+    }
+}
+
+/// Partial text produced by a streaming tool, e.g. the tokens of a sub-agent reply.
+#[cfg(feature = "stream")]
+pub type TextStream = Pin<Box<dyn Stream<Item = String> + Send>>;
+
+/// A tool whose output can be consumed as it is produced.
+///
+/// Implemented by `#[rig_tool(stream)]` for bodies returning
+/// `impl Stream<Item = String>`. rig's `call` collects the whole stream into a
+/// single string result for non-streaming consumers.
+#[cfg(feature = "stream")]
+pub trait StreamingTool: rig::tool::Tool {
+    fn call_streaming(
+        &self,
+        args: Self::Args,
+    ) -> impl Future<Output = Result<TextStream, ToolError>> + Send;
+}
+
+/// Concatenate every item of a text stream.
+#[cfg(feature = "stream")]
+#[doc(hidden)]
+pub async fn collect_text<S>(stream: S) -> String
+where
+    S: Stream<Item = String>,
+{
+    stream.collect().await
+}
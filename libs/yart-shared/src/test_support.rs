@@ -0,0 +1,19 @@
+//! Helpers for testing and debugging tools.
+
+use crate::{check_unknown_fields, deserialize_args, ArgsOf, ToolError, ToolMetadata};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde_json::Value;
+
+/// Deserialize and validate `args` exactly as [`crate::DynTool::call`] would, but
+/// return them instead of running the tool, so no side effects happen.
+pub fn dry_run<T>(_tool: &T, args: Value) -> Result<ArgsOf<T>, ToolError>
+where
+    T: Tool + ToolMetadata,
+    T::Args: JsonSchema,
+{
+    if T::DENY_UNKNOWN_FIELDS {
+        check_unknown_fields(&args, &T::parameters_schema())?;
+    }
+    deserialize_args::<T::Args>(args).map_err(|e| e.with_tool(<T as ToolMetadata>::NAME))
+}
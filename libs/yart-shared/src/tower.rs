@@ -0,0 +1,54 @@
+//! Adapter exposing yart tools as `tower::Service`s.
+
+use crate::dyn_tool::call_json;
+use crate::{ToolError, ToolMetadata};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// A `tower::Service` taking JSON args and returning the tool's serialized output.
+///
+/// Requests are deserialized into `T::Args` the same way as [`crate::DynTool`] does.
+pub struct ServiceAdapter<T> {
+    tool: Arc<T>,
+}
+
+impl<T> ServiceAdapter<T> {
+    pub fn new(tool: T) -> Self {
+        ServiceAdapter {
+            tool: Arc::new(tool),
+        }
+    }
+}
+
+impl<T> Clone for ServiceAdapter<T> {
+    fn clone(&self) -> Self {
+        ServiceAdapter {
+            tool: Arc::clone(&self.tool),
+        }
+    }
+}
+
+impl<T> ::tower::Service<Value> for ServiceAdapter<T>
+where
+    T: Tool<Error = ToolError> + ToolMetadata + 'static,
+    T::Args: JsonSchema,
+{
+    type Response = Value;
+    type Error = ToolError;
+    type Future = Pin<Box<dyn Future<Output = Result<Value, ToolError>> + Send>>;
+
+    // Tools have no backpressure of their own
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ToolError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Value) -> Self::Future {
+        let tool = Arc::clone(&self.tool);
+        Box::pin(async move { call_json(&*tool, request).await })
+    }
+}
@@ -2,8 +2,15 @@ use anyhow::{anyhow, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::future::Future;
 use std::sync::Arc;
-use yart_shared::{derive_parameters, wrap_unsafe, ToolError, ToolOutput};
+use yart_shared::{
+    derive_parameters, derive_parameters_no_format, derive_parameters_titled,
+    derive_parameters_with, derive_parameters_with_id, deserialize_args, redact, rename_keys,
+    schema_diff, tool_definition, wrap_blocking, wrap_unsafe, wrap_unsafe_timeout,
+    wrap_unsafe_with_capacity, Content, ErrorKind, PartialResult, RenameRule, SchemaChange,
+    ToolError, ToolOutput,
+};
 
 #[tokio::test]
 async fn test_wrap_unsafe_success() {
@@ -26,10 +33,61 @@ async fn test_wrap_unsafe_error() {
     assert_eq!(result.unwrap_err().to_string(), "Test error");
 }
 
+#[tokio::test]
+async fn test_wrap_unsafe_with_capacity() {
+    let result = wrap_unsafe_with_capacity(16, || async { Ok(vec![1, 2, 3]) }).await;
+    assert_eq!(result.unwrap(), [1, 2, 3]);
+
+    let result: Result<()> =
+        wrap_unsafe_with_capacity(16, || async { Err(anyhow!("Test error")) }).await;
+    assert_eq!(result.unwrap_err().to_string(), "Test error");
+}
+
+#[tokio::test]
+async fn test_wrap_unsafe_aborted_by_shutdown() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut call = std::pin::pin!(wrap_unsafe(|| async {
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+        Ok(())
+    }));
+
+    // Poll once inside the other runtime so the body is spawned there
+    {
+        let _guard = runtime.enter();
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        assert!(call.as_mut().poll(&mut cx).is_pending());
+    }
+    runtime.shutdown_background();
+
+    assert_eq!(call.await.unwrap_err().to_string(), "tool task aborted");
+}
+
+#[tokio::test]
+async fn test_wrap_blocking_cpu_loop() {
+    let result = wrap_blocking(|| {
+        let mut sum: u64 = 0;
+        for i in 0..1_000_000u64 {
+            sum = sum.wrapping_add(i * i);
+        }
+        Ok(sum)
+    })
+    .await;
+    assert_eq!(result.unwrap(), 333332833333500000);
+}
+
+#[tokio::test]
+async fn test_wrap_blocking_panic() {
+    let result: Result<()> = wrap_blocking(|| panic!("boom")).await;
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Blocking task panicked: boom"
+    );
+}
+
 #[test]
 fn test_tool_error_new() {
     let error = ToolError::new("Custom error");
-    assert_eq!(error.0, "Custom error");
+    assert_eq!(error.message, "Custom error");
     assert_eq!(error.to_string(), "Custom error");
 }
 
@@ -37,25 +95,107 @@ fn test_tool_error_new() {
 fn test_tool_error_from_anyhow() {
     let anyhow_error = anyhow!("Anyhow error");
     let tool_error = ToolError::from(anyhow_error);
-    assert_eq!(tool_error.0, "Anyhow error");
+    assert_eq!(tool_error.message, "Anyhow error");
     assert_eq!(tool_error.to_string(), "Anyhow error");
 }
 
 #[test]
+fn test_tool_error_with_context() {
+    let error = ToolError::new("connection refused")
+        .with_context("querying orders")
+        .with_context("building report")
+        .with_tool("report");
+    assert_eq!(error.message, "connection refused");
+    assert_eq!(error.context, ["querying orders", "building report"]);
+    assert_eq!(
+        error.to_string(),
+        "[report] building report: querying orders: connection refused"
+    );
+
+    let anyhow_error = anyhow!("connection refused").context("querying orders");
+    let error = ToolError::from(anyhow_error);
+    assert_eq!(error.message, "querying orders: connection refused");
+}
+
+#[test]
+#[allow(clippy::io_other_error)]
 fn test_tool_error_from_boxed_error() {
     let boxed_error: Box<dyn std::error::Error + Send + Sync + 'static> = Box::new(
         std::io::Error::new(std::io::ErrorKind::Other, "Boxed error"),
     );
     let tool_error = ToolError::from(boxed_error);
-    assert_eq!(tool_error.0, "Boxed error");
+    assert_eq!(tool_error.message, "Boxed error");
     assert_eq!(tool_error.to_string(), "Boxed error");
 }
 
+#[test]
+fn test_tool_error_from_rig() {
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(std::io::Error::other("Rate limited"));
+    let tool_error = ToolError::from(rig::tool::ToolError::ToolCallError(boxed));
+    assert_eq!(tool_error.message, "Rate limited");
+    assert_eq!(tool_error.kind, ErrorKind::Execution);
+
+    let json_error = serde_json::from_str::<u32>("nope").unwrap_err();
+    let message = json_error.to_string();
+    let tool_error = ToolError::from(rig::tool::ToolError::JsonError(json_error));
+    assert_eq!(tool_error.message, message);
+    assert_eq!(tool_error.kind, ErrorKind::Validation);
+
+    // A yart error that passed through rig keeps its kind and tool name
+    let original = ToolError::timeout("Timed out").with_tool("search");
+    let tool_error = ToolError::from(rig::tool::ToolError::ToolCallError(Box::new(original)));
+    assert_eq!(tool_error.kind, ErrorKind::Timeout);
+    assert_eq!(tool_error.to_string(), "[search] Timed out");
+}
+
+#[test]
+fn test_tool_error_with_tool() {
+    let error = ToolError::new("Custom error");
+    assert_eq!(error.tool, None);
+    assert_eq!(error.to_string(), "Custom error");
+
+    let error = error.with_tool("search");
+    assert_eq!(error.tool.as_deref(), Some("search"));
+    assert_eq!(error.message, "Custom error");
+    assert_eq!(error.to_string(), "[search] Custom error");
+}
+
+#[test]
+fn test_tool_error_is_retryable() {
+    let error = ToolError::new("Query failed");
+    assert_eq!(error.kind, ErrorKind::Execution);
+    assert!(!error.is_retryable());
+    assert!(ToolError::new("Connection reset")
+        .transient()
+        .is_retryable());
+    assert!(ToolError::timeout("Timed out").is_retryable());
+    assert!(!ToolError::validation("Bad args").is_retryable());
+    assert!(!ToolError::serialization("Bad output")
+        .transient()
+        .is_retryable());
+
+    let error = deserialize_args::<DeserializeArgs>(json!({})).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Validation);
+}
+
+#[test]
+fn test_tool_definition_known_fields() {
+    let definition = tool_definition("search", "Searches", json!({ "type": "object" }));
+    // Pins the fields of rig's ToolDefinition; update tool_definition when this changes
+    assert_eq!(
+        serde_json::to_value(&definition).unwrap(),
+        json!({
+            "name": "search",
+            "description": "Searches",
+            "parameters": { "type": "object" }
+        })
+    );
+}
+
 #[test]
 fn test_tool_output_serialization() {
-    let output = ToolOutput {
-        result: json!({ "key": "value" }),
-    };
+    let output = ToolOutput::new(json!({ "key": "value" }));
     let serialized = serde_json::to_string(&output).unwrap();
     assert_eq!(serialized, r#"{"result":{"key":"value"}}"#);
 
@@ -63,6 +203,120 @@ fn test_tool_output_serialization() {
     assert_eq!(deserialized.result, json!({ "key": "value" }));
 }
 
+#[test]
+fn test_tool_output_text_only_format() {
+    let output = ToolOutput::new(json!("plain text"));
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({ "result": "plain text" })
+    );
+
+    let deserialized: ToolOutput =
+        serde_json::from_value(json!({ "result": "plain text" })).unwrap();
+    assert_eq!(deserialized.result, json!("plain text"));
+    assert!(deserialized.content.is_empty());
+}
+
+#[test]
+fn test_tool_output_mixed_content() {
+    let output = ToolOutput::new(json!({ "width": 2 }))
+        .with_content(Content::Text("a chart".to_string()))
+        .with_content(Content::Json(json!([1, 2])))
+        .with_content(Content::Image {
+            mime: "image/png".to_string(),
+            data_base64: "iVBORw0KGgo=".to_string(),
+        });
+    let serialized = serde_json::to_value(&output).unwrap();
+    assert_eq!(
+        serialized,
+        json!({
+            "result": { "width": 2 },
+            "content": [
+                { "text": "a chart" },
+                { "json": [1, 2] },
+                { "image": { "mime": "image/png", "data_base64": "iVBORw0KGgo=" } }
+            ]
+        })
+    );
+
+    let deserialized: ToolOutput = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized.content, output.content);
+}
+
+#[test]
+fn test_tool_output_to_message() {
+    use rig::completion::message::{ContentFormat, ImageMediaType, ToolResultContent, UserContent};
+    use rig::completion::Message;
+    use rig::OneOrMany;
+
+    let output = ToolOutput::new(json!({ "width": 2 })).with_content(Content::Image {
+        mime: "image/png".to_string(),
+        data_base64: "iVBORw0KGgo=".to_string(),
+    });
+    let mut content = OneOrMany::one(ToolResultContent::text(
+        serde_json::to_string(&output).unwrap(),
+    ));
+    content.push(ToolResultContent::image(
+        "iVBORw0KGgo=",
+        Some(ContentFormat::Base64),
+        Some(ImageMediaType::PNG),
+        None,
+    ));
+    assert_eq!(
+        output.to_message("call_1"),
+        Message::User {
+            content: OneOrMany::one(UserContent::tool_result("call_1", content)),
+        }
+    );
+
+    assert_eq!(
+        ToolOutput::new(json!("done")).to_message("call_2"),
+        Message::tool_result("call_2", r#"{"result":"done"}"#)
+    );
+}
+
+#[test]
+fn test_tool_output_meta() {
+    let output = ToolOutput::new(json!({ "id": 7 }))
+        .with_meta("status", 201)
+        .with_meta("etag", "abc");
+    let serialized = serde_json::to_value(&output).unwrap();
+    assert_eq!(
+        serialized,
+        json!({ "result": { "id": 7 }, "meta": { "status": 201, "etag": "abc" } })
+    );
+
+    let deserialized: ToolOutput = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized.meta, output.meta);
+
+    let output = ToolOutput::new(json!(1));
+    assert!(output.meta.is_empty());
+    assert_eq!(serde_json::to_string(&output).unwrap(), r#"{"result":1}"#);
+}
+
+#[test]
+fn test_rename_keys() {
+    let value = json!({ "user_name": "ada", "items": [{ "item_id": 1 }], "HTTPCode": 200 });
+    assert_eq!(
+        rename_keys(value.clone(), RenameRule::CamelCase),
+        json!({ "userName": "ada", "items": [{ "itemId": 1 }], "httpcode": 200 })
+    );
+    assert_eq!(
+        rename_keys(value, "SCREAMING-KEBAB-CASE".parse().unwrap()),
+        json!({ "USER-NAME": "ada", "ITEMS": [{ "ITEM-ID": 1 }], "HTTPCODE": 200 })
+    );
+    assert!("Title Case".parse::<RenameRule>().is_err());
+}
+
+#[test]
+fn test_redact() {
+    let value = json!({ "token": "t", "nested": { "password": "p", "user": "u" }, "list": [{ "token": 1 }] });
+    assert_eq!(
+        redact(value, &["token", "password"]),
+        json!({ "token": "***", "nested": { "password": "***", "user": "u" }, "list": [{ "token": "***" }] })
+    );
+}
+
 #[test]
 fn test_derive_parameters() {
     #[derive(Serialize, Deserialize, JsonSchema)]
@@ -85,6 +339,207 @@ fn test_derive_parameters() {
     assert_eq!(schema, expected);
 }
 
+#[test]
+fn test_derive_parameters_titled() {
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Args {
+        input: String,
+    }
+
+    let schema = derive_parameters_titled::<Args>("search_query");
+    let mut expected = derive_parameters::<Args>();
+    assert_eq!(expected["title"], "Args");
+    expected["title"] = json!("search_query");
+    assert_eq!(schema, expected);
+    assert_eq!(schema["properties"]["input"], json!({ "type": "string" }));
+}
+
+#[test]
+fn test_derive_parameters_with() {
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Args {
+        input: String,
+    }
+
+    let schema = derive_parameters_with::<Args>(|schema| {
+        schema.as_object_mut().unwrap().remove("$schema");
+    });
+    let mut expected = derive_parameters::<Args>();
+    assert!(expected.get("$schema").is_some());
+    expected.as_object_mut().unwrap().remove("$schema");
+    assert_eq!(schema, expected);
+}
+
+#[test]
+fn test_derive_parameters_with_id() {
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Args {
+        input: String,
+    }
+
+    let id = "https://schemas.example.com/tools/args.json";
+    let schema = derive_parameters_with_id::<Args>(id);
+    assert_eq!(schema["$id"], json!(id));
+    assert_eq!(
+        schema["properties"],
+        derive_parameters::<Args>()["properties"]
+    );
+}
+
+#[test]
+fn test_derive_parameters_no_format() {
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Range {
+        start: i32,
+        end: u32,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct FormatArgs {
+        count: i32,
+        limits: Vec<u32>,
+        range: Range,
+        format: String,
+    }
+
+    let schema = derive_parameters_no_format::<FormatArgs>();
+    assert!(!schema.to_string().contains("int32"));
+    assert_eq!(schema["properties"]["count"], json!({ "type": "integer" }));
+    assert_eq!(
+        schema["definitions"]["Range"]["properties"]["end"],
+        json!({ "type": "integer", "minimum": 0.0 })
+    );
+    // A field named `format` is not a keyword
+    assert_eq!(schema["properties"]["format"], json!({ "type": "string" }));
+}
+
+#[test]
+fn test_derive_parameters_serde_default() {
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct DefaultArgs {
+        input: String,
+        #[serde(default)]
+        count: u32,
+    }
+
+    let schema = derive_parameters::<DefaultArgs>();
+    assert_eq!(schema["properties"]["count"]["default"], json!(0));
+    assert_eq!(schema["required"], json!(["input"]));
+
+    // The default survives a serialization round trip
+    let round_trip: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&schema).unwrap()).unwrap();
+    assert_eq!(round_trip["properties"]["count"]["default"], json!(0));
+}
+
+#[test]
+fn test_derive_parameters_flatten() {
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct Paging {
+        limit: u32,
+        #[serde(default)]
+        offset: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct SearchArgs {
+        query: String,
+        #[serde(flatten)]
+        paging: Paging,
+    }
+
+    let schema = derive_parameters::<SearchArgs>();
+    assert!(schema.get("allOf").is_none());
+    let mut properties: Vec<&String> = schema["properties"].as_object().unwrap().keys().collect();
+    properties.sort();
+    assert_eq!(properties, ["limit", "offset", "query"]);
+    assert_eq!(schema["required"], json!(["limit", "query"]));
+
+    let args: SearchArgs = deserialize_args(json!({ "query": "q", "limit": 5 })).unwrap();
+    assert_eq!(args.paging.limit, 5);
+    assert_eq!(args.paging.offset, 0);
+}
+
+#[test]
+fn test_derive_parameters_enum_field() {
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    enum Mode {
+        Fast,
+        Accurate,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct ModeArgs {
+        query: String,
+        mode: Mode,
+    }
+
+    let schema = derive_parameters::<ModeArgs>();
+    assert_eq!(
+        schema["properties"]["mode"],
+        json!({ "$ref": "#/definitions/Mode" })
+    );
+    assert_eq!(
+        schema["definitions"]["Mode"],
+        json!({ "type": "string", "enum": ["Fast", "Accurate"] })
+    );
+
+    let err = deserialize_args::<ModeArgs>(json!({ "query": "q", "mode": "Slow" })).unwrap_err();
+    assert!(err
+        .message
+        .contains("unknown variant `Slow`, expected `Fast` or `Accurate`"));
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct DeserializeArgs {
+    input: String,
+    count: u32,
+}
+
+#[test]
+fn test_deserialize_args_success() {
+    let args: DeserializeArgs = deserialize_args(json!({ "input": "hi", "count": 2 })).unwrap();
+    assert_eq!(args.input, "hi");
+    assert_eq!(args.count, 2);
+}
+
+#[test]
+fn test_deserialize_args_missing_field() {
+    let err = deserialize_args::<DeserializeArgs>(json!({ "input": "hi" })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid arguments: missing required field `count` (expected integer). \
+         Call the tool again with arguments matching its parameter schema."
+    );
+}
+
+#[test]
+fn test_deserialize_args_type_mismatch() {
+    let err =
+        deserialize_args::<DeserializeArgs>(json!({ "input": "hi", "count": "two" })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid arguments: field `count` is invalid: invalid type: string \"two\", \
+         expected u32 (expected integer). \
+         Call the tool again with arguments matching its parameter schema."
+    );
+}
+
+#[test]
+fn test_deserialize_args_bare_value() {
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct SingleArgs {
+        input: String,
+    }
+
+    let args: SingleArgs = deserialize_args(json!("hi")).unwrap();
+    assert_eq!(args.input, "hi");
+
+    // Args with several fields aren't guessed at
+    let err = deserialize_args::<DeserializeArgs>(json!("hi")).unwrap_err();
+    assert!(err.message.contains("expected struct DeserializeArgs"));
+}
+
 #[tokio::test]
 async fn test_wrap_unsafe_with_context() {
     #[derive(Clone)]
@@ -103,3 +558,135 @@ async fn test_wrap_unsafe_with_context() {
     let result = wrap_unsafe(move || sample_async(ctx_clone)).await;
     assert_eq!(result.unwrap(), "Context");
 }
+
+#[test]
+fn test_partial_result_serializes_into_output() {
+    let partial: PartialResult<u32> = [1, 2, 3, 4]
+        .into_iter()
+        .map(|id| match id {
+            3 => Err(ToolError::new("item 3 is locked").with_context("processing item 3")),
+            4 => Err(ToolError::validation("item 4 is malformed")),
+            id => Ok(id * 10),
+        })
+        .collect();
+    assert!(!partial.is_complete());
+
+    let output = ToolOutput::new(serde_json::to_value(&partial).unwrap());
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({
+            "result": {
+                "succeeded": [10, 20],
+                "failed": [
+                    { "error": "processing item 3: item 3 is locked", "kind": "execution" },
+                    { "error": "item 4 is malformed", "kind": "validation" }
+                ]
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_wrap_unsafe_timeout() {
+    let fast = wrap_unsafe_timeout(std::time::Duration::from_secs(1), || async { Ok(7) }).await;
+    assert_eq!(fast.unwrap(), 7);
+
+    let slow = wrap_unsafe_timeout(std::time::Duration::from_millis(10), || async {
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        Ok(7)
+    })
+    .await;
+    let err = ToolError::from(slow.unwrap_err());
+    assert_eq!(err.kind, ErrorKind::Timeout);
+    assert_eq!(err.message, "Timed out after 10ms");
+}
+
+#[test]
+fn test_schema_diff_added_required_field_is_breaking() {
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct V1 {
+        query: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct V2 {
+        query: String,
+        limit: u32,
+    }
+
+    let changes = schema_diff(&derive_parameters::<V1>(), &derive_parameters::<V2>());
+    assert_eq!(
+        changes,
+        [SchemaChange::PropertyAdded {
+            name: "limit".to_string(),
+            required: true,
+        }]
+    );
+    assert!(changes[0].is_breaking());
+}
+
+#[test]
+fn test_schema_diff_added_optional_field_is_not_breaking() {
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct V1 {
+        query: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct V2 {
+        query: String,
+        limit: Option<u32>,
+    }
+
+    let changes = schema_diff(&derive_parameters::<V1>(), &derive_parameters::<V2>());
+    assert_eq!(
+        changes,
+        [SchemaChange::PropertyAdded {
+            name: "limit".to_string(),
+            required: false,
+        }]
+    );
+    assert!(!changes.iter().any(SchemaChange::is_breaking));
+}
+
+#[test]
+fn test_schema_diff_removed_retyped_and_required_changes() {
+    let old = json!({
+        "properties": {
+            "a": { "type": "string" },
+            "b": { "type": "integer" },
+            "c": { "type": "boolean" }
+        },
+        "required": ["a"]
+    });
+    let new = json!({
+        "properties": {
+            "a": { "type": "string" },
+            "b": { "type": "string" }
+        },
+        "required": ["b"]
+    });
+    assert_eq!(
+        schema_diff(&old, &new),
+        [
+            SchemaChange::BecameOptional {
+                name: "a".to_string()
+            },
+            SchemaChange::PropertyRetyped {
+                name: "b".to_string(),
+                old: json!("integer"),
+                new: json!("string"),
+            },
+            SchemaChange::BecameRequired {
+                name: "b".to_string()
+            },
+            SchemaChange::PropertyRemoved {
+                name: "c".to_string()
+            },
+        ]
+    );
+}
@@ -1,2 +1,2 @@
-pub use yart_macro::rig_tool;
+pub use yart_macro::{rig_tool, rig_tools, tool, ToolArgs};
 pub use yart_shared::*;